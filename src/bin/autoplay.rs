@@ -0,0 +1,187 @@
+use std::{
+    env,
+    fs::File,
+    path,
+    time::{Duration, Instant},
+};
+
+use ggsweep::{
+    board::{bot, Board, Generation, Status, Topology},
+    config::{Difficulty, GameConfig},
+    err_here,
+    error::{LocatedError, WrappedError},
+};
+
+/// The command line options of the bot
+struct Options {
+    games: usize,
+    config: path::PathBuf,
+    difficulty: Option<Difficulty>,
+    generation: Option<Generation>,
+    topology: Option<Topology>,
+    /// The seed of the first game, the games after it use the following seeds
+    seed: Option<u64>,
+}
+
+/// Plays games with the solver bot without opening a window and prints how it did.
+///
+/// `cargo run --release --bin autoplay -- --games 1000 --difficulty expert --generation NoGuess --topology Hex`
+fn main() -> Result<(), LocatedError> {
+    // The warnings of single games would drown the results
+    simple_logger::SimpleLogger::new()
+        .with_level(log::LevelFilter::Error)
+        .init()
+        .unwrap();
+
+    let options = parse_arguments()?;
+
+    let file = File::open(&options.config).map_err(err_here!())?;
+    let mut game_config: GameConfig = ron::de::from_reader(file).map_err(err_here!())?;
+    if let Some(difficulty) = options.difficulty {
+        game_config = difficulty.apply(&game_config);
+    }
+    if let Some(generation) = options.generation {
+        game_config.generation = generation;
+    }
+    if let Some(topology) = options.topology {
+        game_config.topology = topology;
+    }
+    game_config
+        .validate()
+        .map_err(WrappedError::ArgumentError)
+        .map_err(err_here!())?;
+
+    let first_seed = options
+        .seed
+        .or(game_config.seed)
+        .unwrap_or_else(rand::random);
+
+    let mut wins = 0;
+    let mut guesses = 0;
+    let mut time = Duration::default();
+    for game in 0..options.games {
+        let board = Board::new(
+            game_config.game_size,
+            game_config.number_of_mines,
+            first_seed.wrapping_add(game as u64),
+        )
+        .set_generation(game_config.generation)
+        .set_win_condition(game_config.win_condition)
+        .set_topology(game_config.topology);
+
+        // The time includes generating the mines since that is what differs between the generations
+        let start = Instant::now();
+        let result = bot::play(board);
+        time += start.elapsed();
+
+        if result.status == Status::Won {
+            wins += 1;
+        }
+        guesses += result.guesses;
+    }
+
+    let games = options.games.max(1) as f64;
+    println!(
+        "Played {} games on {}x{} {} with {} mines, {} generation, seeds from {}",
+        options.games,
+        game_config.game_size.0,
+        game_config.game_size.1,
+        game_config.topology.name(),
+        game_config.number_of_mines,
+        game_config.generation.name(),
+        first_seed
+    );
+    println!("Won: {} ({:.1}%)", wins, wins as f64 / games * 100.0);
+    println!("Guesses per game: {:.2}", guesses as f64 / games);
+    println!(
+        "Time per game: {:.2} ms",
+        time.as_secs_f64() * 1000.0 / games
+    );
+
+    Ok(())
+}
+
+/// Reads `--games <n>`, `--config <path>`, `--difficulty <name>`, `--generation <mode>`, `--topology <shape>`
+/// and `--seed <seed>`
+fn parse_arguments() -> Result<Options, LocatedError> {
+    let mut options = Options {
+        games: 100,
+        config: default_config_path(),
+        difficulty: None,
+        generation: None,
+        topology: None,
+        seed: None,
+    };
+
+    let mut args = env::args().skip(1);
+    while let Some(arg) = args.next() {
+        let value = args
+            .next()
+            .ok_or_else(|| WrappedError::ArgumentError(format!("{} needs a value", arg)))
+            .map_err(err_here!())?;
+
+        match arg.as_str() {
+            "--games" => options.games = parse_number(&value)?,
+            "--seed" => options.seed = Some(parse_number(&value)?),
+            "--config" => options.config = path::PathBuf::from(value),
+            "--difficulty" => {
+                let difficulty = Difficulty::ALL
+                    .iter()
+                    .find(|difficulty| difficulty.name().eq_ignore_ascii_case(&value))
+                    .ok_or_else(|| {
+                        WrappedError::ArgumentError(format!("{} is not a difficulty", value))
+                    })
+                    .map_err(err_here!())?;
+                options.difficulty = Some(*difficulty);
+            }
+            "--generation" => {
+                let generation = ron::de::from_str(&value)
+                    .map_err(|_| {
+                        WrappedError::ArgumentError(format!(
+                            "{} is not a generation, use Classic, SafeZone or NoGuess",
+                            value
+                        ))
+                    })
+                    .map_err(err_here!())?;
+                options.generation = Some(generation);
+            }
+            "--topology" => {
+                let topology = ron::de::from_str(&value)
+                    .map_err(|_| {
+                        WrappedError::ArgumentError(format!(
+                            "{} is not a topology, use Square, Hex or Triangle",
+                            value
+                        ))
+                    })
+                    .map_err(err_here!())?;
+                options.topology = Some(topology);
+            }
+            _ => {
+                return Err(WrappedError::ArgumentError(format!(
+                    "Unknown argument {}",
+                    arg
+                )))
+                .map_err(err_here!())
+            }
+        }
+    }
+
+    Ok(options)
+}
+
+fn parse_number<T: std::str::FromStr>(value: &str) -> Result<T, LocatedError> {
+    value
+        .parse()
+        .map_err(|_| WrappedError::ArgumentError(format!("{} is not a valid number", value)))
+        .map_err(err_here!())
+}
+
+/// The config in resources, the same one the game starts from
+fn default_config_path() -> path::PathBuf {
+    let mut path = env::var("CARGO_MANIFEST_DIR")
+        .map(path::PathBuf::from)
+        .unwrap_or_else(|_| path::PathBuf::from("."));
+    path.push("resources");
+    path.push("config.ron");
+    path
+}
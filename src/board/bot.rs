@@ -0,0 +1,69 @@
+use std::cmp::Ordering;
+
+use super::{probability, solver::Solver, Board, Mark, SquareState, Status};
+
+/// How a game played by the bot went
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct BotGame {
+    /// Won or lost, the bot always plays until the game is over
+    pub status: Status,
+    /// The squares opened without being proven safe, the first square is not counted
+    pub guesses: usize,
+}
+
+/// Plays the board to the end starting in the middle.
+/// Every square the solver can prove is opened or flagged and when nothing can be proven
+/// the square least likely to be a mine is opened.
+/// Mines are flagged as they are found so every win condition can be met
+pub fn play(mut board: Board) -> BotGame {
+    let mut solver = Solver::new();
+    let mut guesses = 0;
+
+    let (width, height) = board.size();
+    board.reveal(board.point_to_index(cgmath::vec2(width as i32 / 2, height as i32 / 2)));
+
+    while board.status() == Status::InGame {
+        let deductions = solver.deduce(&board);
+
+        for deduction in &deductions {
+            if deduction.is_mine {
+                solver.mark_mine(deduction.index);
+                if board.square(deduction.index) == &SquareState::Closed(Mark::None) {
+                    board.toggle_flag(deduction.index);
+                }
+            } else {
+                board.reveal(deduction.index);
+            }
+        }
+
+        if !deductions.is_empty() {
+            continue;
+        }
+
+        // The mines the solver knows are proven so the probabilities always fit
+        let probabilities =
+            probability::mine_probabilities(&board, solver.known_mines()).unwrap_or_default();
+        let guess = probabilities
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !solver.known_mines().contains(index))
+            .filter_map(|(index, probability)| probability.map(|p| (index, p)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal));
+
+        match guess {
+            Some((index, probability)) => {
+                // The solver misses some safe squares that only show up in the probabilities
+                if probability > 0.0 {
+                    guesses += 1;
+                }
+                board.reveal(index);
+            }
+            None => break,
+        }
+    }
+
+    BotGame {
+        status: board.status(),
+        guesses,
+    }
+}
@@ -0,0 +1,587 @@
+use std::collections::HashSet;
+
+use log::{trace, warn};
+use rand::prelude::*;
+use rand_chacha::ChaCha8Rng;
+use serde::{Deserialize, Serialize};
+
+use super::{solver, Layout, Topology};
+
+//Types
+/// # Index Type
+/// The type used for indices
+pub type IndexType = usize;
+
+/// The mark the player has put on a closed square
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum Mark {
+    #[default]
+    None,
+    /// The player is sure there is a mine under the square
+    Flag,
+    /// The player thinks there might be a mine under the square
+    Question,
+}
+
+/// The state of a square
+/// A square can either be closed with the mark the player has put on it
+/// or it can be open and then the number represents the number of neighboring mines
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub enum SquareState {
+    Closed(Mark),
+    Open(u8),
+}
+
+/// The status of the game played on a board
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Status {
+    InGame,
+    Won,
+    Lost,
+}
+
+/// How the mines are placed when the first square is revealed
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum Generation {
+    /// Mines can be anywhere except the first square
+    #[default]
+    Classic,
+    /// The first square and its neighbors are free from mines so the first square always opens up an area.
+    /// Boards too dense to fit the mines outside of the safe zone only keep the first square free
+    SafeZone,
+    /// Like the safe zone but the mines are regenerated until the board can be solved without guessing
+    NoGuess,
+}
+
+impl Generation {
+    pub fn name(&self) -> &'static str {
+        match self {
+            Generation::Classic => "Classic",
+            Generation::SafeZone => "Safe zone",
+            Generation::NoGuess => "No guess",
+        }
+    }
+}
+
+/// What the player has to do to win a game
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum WinCondition {
+    /// Open every square that is not a mine
+    #[default]
+    RevealAllSafe,
+    /// Place flags on every mine without any flags on safe squares
+    FlagAllMines,
+    /// Both open every safe square and flag every mine
+    Both,
+}
+
+/// # Board
+/// The rules of mine sweeper without anything to do with drawing or input.
+/// The mines are generated when the first square is revealed so the first square is never a mine,
+/// unless they are placed from a layout.
+/// The mines are generated from the seed so the same seed and first square always gives the same board.
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Board {
+    size: (usize, usize),
+    number_of_mines: usize,
+    seed: u64,
+    generation: Generation,
+    win_condition: WinCondition,
+    /// The shape of the squares and which squares are neighbors
+    #[serde(default)]
+    topology: Topology,
+    /// Wether marking a flagged square puts a question mark on it
+    #[serde(default)]
+    question_marks: bool,
+    grid: Vec<SquareState>,
+    mines: HashSet<IndexType>,
+    generated: bool,
+    status: Status,
+    /// The mine that was revealed and lost the game
+    #[serde(default)]
+    detonated: Option<IndexType>,
+}
+
+impl Board {
+    /// Create a new board where all squares are closed and no mines are generated yet.
+    /// The amount of mines is capped so that there is always at least one safe square
+    pub fn new(size: (usize, usize), number_of_mines: usize, seed: u64) -> Self {
+        let max_mines = (size.0 * size.1).saturating_sub(1);
+        if number_of_mines > max_mines {
+            warn!(
+                "{} mines does not fit on a {}x{} board, using {} mines",
+                number_of_mines, size.0, size.1, max_mines
+            );
+        }
+        let number_of_mines = number_of_mines.min(max_mines);
+
+        Board {
+            size,
+            number_of_mines,
+            seed,
+            generation: Generation::default(),
+            win_condition: WinCondition::default(),
+            topology: Topology::default(),
+            question_marks: false,
+            grid: vec![SquareState::Closed(Mark::None); size.0 * size.1],
+            mines: HashSet::new(),
+            generated: false,
+            status: Status::InGame,
+            detonated: None,
+        }
+    }
+
+    /// Set how the mines will be generated
+    pub fn set_generation(mut self, generation: Generation) -> Self {
+        self.generation = generation;
+        self
+    }
+
+    /// Place the mines where they are given instead of generating them when the first square is opened
+    pub fn set_mines(mut self, mines: impl IntoIterator<Item = IndexType>) -> Self {
+        self.mines = mines.into_iter().collect();
+        self.number_of_mines = self.mines.len();
+        self.generated = true;
+        self
+    }
+
+    /// Set what the player has to do to win
+    pub fn set_win_condition(mut self, win_condition: WinCondition) -> Self {
+        self.win_condition = win_condition;
+        self
+    }
+
+    /// Set the shape of the squares and which squares are neighbors
+    pub fn set_topology(mut self, topology: Topology) -> Self {
+        self.topology = topology;
+        self
+    }
+
+    /// Set wether marking a flagged square puts a question mark on it instead of clearing it
+    pub fn set_question_marks(mut self, question_marks: bool) -> Self {
+        self.question_marks = question_marks;
+        self
+    }
+
+    /// Place the mines of the layout now instead of generating them when the first square is revealed.
+    /// The size and the amount of mines of the board are taken from the layout and the first square can be a mine
+    pub fn set_layout(mut self, layout: &Layout) -> Self {
+        self.size = layout.size();
+        self.grid = vec![SquareState::Closed(Mark::None); self.size.0 * self.size.1];
+        self.set_mines(layout.mines())
+    }
+
+    /// A new board with the same settings and the same mines where every square is closed again.
+    /// The mines stay where they are wherever the first square is, boards without mines generate them from the seed
+    pub fn restart(&self) -> Self {
+        Board {
+            grid: vec![SquareState::Closed(Mark::None); self.grid.len()],
+            status: Status::InGame,
+            detonated: None,
+            ..self.clone()
+        }
+    }
+
+    /// The width and height of the board in squares
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// The shape of the squares
+    pub fn topology(&self) -> Topology {
+        self.topology
+    }
+
+    /// The total amount of squares on the board
+    pub fn len(&self) -> usize {
+        self.grid.len()
+    }
+
+    /// Returns true if the board has no squares
+    pub fn is_empty(&self) -> bool {
+        self.grid.is_empty()
+    }
+
+    /// The amount of mines on the board
+    pub fn number_of_mines(&self) -> usize {
+        self.number_of_mines
+    }
+
+    /// The seed used to generate the mines
+    pub fn seed(&self) -> u64 {
+        self.seed
+    }
+
+    /// The current status of the game
+    pub fn status(&self) -> Status {
+        self.status
+    }
+
+    /// Get the state of a square
+    pub fn square(&self, index: IndexType) -> &SquareState {
+        &self.grid[index]
+    }
+
+    /// Get the state of all the squares
+    pub fn squares(&self) -> &[SquareState] {
+        &self.grid
+    }
+
+    /// Returns true if there is a mine on the square.
+    /// Will always be false before the mines are generated
+    pub fn is_mine(&self, index: IndexType) -> bool {
+        self.mines.contains(&index)
+    }
+
+    /// Get the indices of all the mines
+    pub fn mines(&self) -> &HashSet<IndexType> {
+        &self.mines
+    }
+
+    /// The mine that lost the game if it has been lost
+    pub fn detonated(&self) -> Option<IndexType> {
+        self.detonated
+    }
+
+    /// Returns true if the mines have been generated
+    pub fn is_generated(&self) -> bool {
+        self.generated
+    }
+
+    /// Put a square back the way it was, used to undo moves
+    pub(super) fn restore_square(&mut self, index: IndexType, state: SquareState) {
+        self.grid[index] = state;
+    }
+
+    /// Put the status and the detonated mine back the way they were, used to undo moves
+    pub(super) fn restore_status(&mut self, status: Status, detonated: Option<IndexType>) {
+        self.status = status;
+        self.detonated = detonated;
+    }
+
+    /// Remove the generated mines so the next reveal generates them again, used to undo the first move
+    pub(super) fn remove_mines(&mut self) {
+        self.mines.clear();
+        self.generated = false;
+    }
+
+    /// Returns true once a square has been opened
+    pub fn is_started(&self) -> bool {
+        self.grid
+            .iter()
+            .any(|square| matches!(square, SquareState::Open(_)))
+    }
+
+    /// Converts from a linear index to a 2 dimensional point.
+    ///
+    /// returns:
+    /// x = index % game width
+    /// y = index / game width
+    pub fn index_to_point(&self, i: IndexType) -> cgmath::Vector2<i32> {
+        cgmath::Vector2::new((i % self.size.0) as i32, (i / self.size.0) as i32)
+    }
+
+    /// convert from a point to a index
+    ///
+    /// returns:
+    /// x + y * game width
+    pub fn point_to_index(&self, point: cgmath::Vector2<i32>) -> IndexType {
+        point.x as usize + point.y as usize * self.size.0
+    }
+
+    /// Returns true if the point is inside the board
+    pub fn contains_point(&self, point: cgmath::Vector2<i32>) -> bool {
+        point.x >= 0 && point.y >= 0 && point.x < self.size.0 as i32 && point.y < self.size.1 as i32
+    }
+
+    /// Gets the indices for all of the neighbors to a square, the topology of the board decides which they are
+    pub fn get_neighbors(&self, index: IndexType) -> [Option<IndexType>; Topology::MAX_NEIGHBORS] {
+        let point = self.index_to_point(index);
+        let mut i = 0;
+        let mut neighbors = [Option::<IndexType>::None; Topology::MAX_NEIGHBORS];
+        //Loop through all neighbors
+        for &(x, y) in self.topology.neighbor_offsets(point) {
+            let current_point = point + cgmath::vec2(x, y);
+
+            if !self.contains_point(current_point) {
+                continue;
+            }
+
+            neighbors[i] = Some(self.point_to_index(current_point));
+            i += 1;
+        }
+
+        neighbors
+    }
+
+    /// Counts the amount of neighboring squares with mines
+    pub fn count_neighbors(&self, index: IndexType) -> u8 {
+        self.get_neighbors(index)
+            .iter()
+            .flatten()
+            .filter(|neighbor| self.mines.contains(neighbor))
+            .count() as u8
+    }
+
+    /// Opens a square and checks the amount of neighboring mines.
+    /// Squares without any neighboring mines will open their neighbors as well.
+    /// If mines aren't generated it will generate them first.
+    /// Flagged or already open squares are left as they are, question marks do not protect a square.
+    pub fn reveal(&mut self, index: IndexType) {
+        if self.status != Status::InGame {
+            return;
+        }
+
+        match self.grid[index] {
+            SquareState::Closed(Mark::None) | SquareState::Closed(Mark::Question) => {}
+            SquareState::Closed(Mark::Flag) | SquareState::Open(_) => return,
+        }
+
+        if !self.is_generated() {
+            self.generate_mines(index);
+        }
+
+        if self.mines.contains(&index) {
+            self.grid[index] = SquareState::Open(self.count_neighbors(index));
+            self.status = Status::Lost;
+            self.detonated = Some(index);
+            return;
+        }
+
+        let mut to_open = vec![index];
+        while let Some(current) = to_open.pop() {
+            if let SquareState::Open(_) = self.grid[current] {
+                continue;
+            }
+
+            let neighbor_count = self.count_neighbors(current);
+            self.grid[current] = SquareState::Open(neighbor_count);
+
+            if neighbor_count > 0 {
+                continue;
+            }
+
+            for &neighbor in self.get_neighbors(current).iter().flatten() {
+                if let SquareState::Closed(_) = self.grid[neighbor] {
+                    to_open.push(neighbor);
+                }
+            }
+        }
+
+        self.check_win();
+    }
+
+    /// Opens all closed neighbors of an open square that has as many flags around it as its number.
+    /// A wrongly placed flag means one of the opened neighbors is a mine and the game is lost
+    pub fn chord(&mut self, index: IndexType) {
+        if self.status != Status::InGame {
+            return;
+        }
+
+        if let SquareState::Open(count) = self.grid[index] {
+            let neighbors = self.get_neighbors(index);
+            let flags = neighbors
+                .iter()
+                .flatten()
+                .filter(|&&neighbor| self.grid[neighbor] == SquareState::Closed(Mark::Flag))
+                .count();
+
+            if flags != count as usize {
+                return;
+            }
+
+            for &neighbor in neighbors.iter().flatten() {
+                self.reveal(neighbor);
+            }
+        }
+    }
+
+    /// Go to the next mark on a closed square.
+    /// The marks go from none to flag and back, through a question mark if question marks are used
+    pub fn toggle_flag(&mut self, index: IndexType) {
+        if self.status != Status::InGame {
+            return;
+        }
+
+        if let SquareState::Closed(mark) = self.grid[index] {
+            let next = match mark {
+                Mark::None => Mark::Flag,
+                Mark::Flag if self.question_marks => Mark::Question,
+                Mark::Flag | Mark::Question => Mark::None,
+            };
+            self.grid[index] = SquareState::Closed(next);
+            self.check_win();
+        }
+    }
+
+    /// Returns true if every square that is not a mine has been opened
+    pub fn all_safe_revealed(&self) -> bool {
+        self.is_generated() && self.mines == self.get_closed_squares()
+    }
+
+    /// Returns true if every mine has a flag and there are no flags on safe squares
+    pub fn all_mines_flagged(&self) -> bool {
+        self.is_generated() && self.mines == self.get_flagged_squares()
+    }
+
+    /// The 3BV of the board, the least amount of clicks needed to open every safe square.
+    /// Every opening counts as one click and so does every number that is not next to an opening.
+    /// It is zero until the mines have been generated
+    pub fn three_bv(&self) -> usize {
+        self.three_bv_squares().len()
+    }
+
+    /// The part of the 3BV that has been opened, the openings and lone numbers that are open
+    pub fn solved_three_bv(&self) -> usize {
+        self.three_bv_squares()
+            .into_iter()
+            .filter(|index| matches!(self.grid[*index], SquareState::Open(_)))
+            .count()
+    }
+
+    /// One square for every click counted in the 3BV.
+    /// Revealing any empty square of an opening opens all of it so one square stands for the whole opening
+    fn three_bv_squares(&self) -> Vec<IndexType> {
+        if !self.is_generated() {
+            return vec![];
+        }
+
+        let mut counted = vec![false; self.len()];
+        let mut squares = vec![];
+
+        // Flood fill the openings the same way reveal does
+        for index in 0..self.len() {
+            if counted[index] || self.mines.contains(&index) || self.count_neighbors(index) > 0 {
+                continue;
+            }
+
+            squares.push(index);
+            let mut to_visit = vec![index];
+            while let Some(current) = to_visit.pop() {
+                if counted[current] {
+                    continue;
+                }
+                counted[current] = true;
+
+                if self.count_neighbors(current) > 0 {
+                    continue;
+                }
+
+                for &neighbor in self.get_neighbors(current).iter().flatten() {
+                    if !counted[neighbor] {
+                        to_visit.push(neighbor);
+                    }
+                }
+            }
+        }
+
+        // Numbers that are not opened by an opening need a click each
+        squares.extend(
+            (0..self.len()).filter(|index| !counted[*index] && !self.mines.contains(index)),
+        );
+        squares
+    }
+
+    /// Checks the win condition of the board and sets the status to won if it is met.
+    /// This is the only place where a game can be won
+    fn check_win(&mut self) {
+        let won = match self.win_condition {
+            WinCondition::RevealAllSafe => self.all_safe_revealed(),
+            WinCondition::FlagAllMines => self.all_mines_flagged(),
+            WinCondition::Both => self.all_safe_revealed() && self.all_mines_flagged(),
+        };
+
+        if won {
+            self.status = Status::Won;
+        }
+    }
+
+    /// Number of layouts the no guess generation tries before settling for a board that needs guessing
+    const NO_GUESS_ATTEMPTS: usize = 1000;
+
+    /// Generate the mines using the generation mode of the board and a rng seeded with the board seed
+    fn generate_mines(&mut self, first_index: IndexType) {
+        let mut rng = ChaCha8Rng::seed_from_u64(self.seed);
+
+        let mut excluded = HashSet::new();
+        excluded.insert(first_index);
+        if self.generation != Generation::Classic {
+            excluded.extend(self.get_neighbors(first_index).iter().flatten());
+
+            // A board too dense for the safe zone only spares the first square
+            if self.grid.len() - excluded.len() < self.number_of_mines {
+                warn!(
+                    "{} mines does not leave room for a safe zone, only the first square is kept free",
+                    self.number_of_mines
+                );
+                excluded.clear();
+                excluded.insert(first_index);
+            }
+        }
+
+        self.mines = self.random_mines(&mut rng, &excluded);
+        self.generated = true;
+
+        if self.generation == Generation::NoGuess {
+            let mut attempts = 1;
+            while !solver::is_solvable(self, first_index) {
+                if attempts == Self::NO_GUESS_ATTEMPTS {
+                    warn!(
+                        "Could not generate a board without guessing in {} attempts",
+                        attempts
+                    );
+                    break;
+                }
+
+                self.mines = self.random_mines(&mut rng, &excluded);
+                attempts += 1;
+            }
+
+            trace!("No guess board generated after {} attempts", attempts);
+        }
+    }
+
+    /// Pick random slots for the mines among the squares that are not in the excluded set.
+    /// There has to be at least as many squares left as there are mines
+    fn random_mines(
+        &self,
+        rng: &mut ChaCha8Rng,
+        excluded: &HashSet<IndexType>,
+    ) -> HashSet<IndexType> {
+        let mut candidates: Vec<IndexType> = (0..self.grid.len())
+            .filter(|i| !excluded.contains(i))
+            .collect();
+
+        let (picked, _) = candidates.partial_shuffle(rng, self.number_of_mines);
+        let result: HashSet<IndexType> = picked.iter().copied().collect();
+
+        trace!("Mines generated at {:?} from seed {}", result, self.seed);
+        result
+    }
+
+    /// Get all the indices for the squares that are still closed
+    pub fn get_closed_squares(&self) -> HashSet<IndexType> {
+        let iter = self.grid.iter().enumerate().filter_map(|s| {
+            if let SquareState::Closed(_) = s.1 {
+                Some(s.0)
+            } else {
+                None
+            }
+        });
+
+        iter.collect()
+    }
+
+    /// Get all the indices for the squares that has a flag on them
+    pub fn get_flagged_squares(&self) -> HashSet<IndexType> {
+        let iter = self.grid.iter().enumerate().filter_map(|s| {
+            if let SquareState::Closed(Mark::Flag) = s.1 {
+                Some(s.0)
+            } else {
+                None
+            }
+        });
+
+        iter.collect()
+    }
+}
@@ -0,0 +1,142 @@
+use std::{
+    cmp::Ordering,
+    sync::atomic::{self, AtomicBool},
+};
+
+use super::{
+    probability,
+    solver::{Deduction, Rule, Solver},
+    Board, IndexType, Mark, SquareState, Status,
+};
+
+/// A square the player is pointed to when they ask for help
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Hint {
+    /// Nothing is open yet and the first square opened is never a mine
+    FirstSquare(IndexType),
+    /// A square the solver has proven to be safe or a mine
+    Deduction(Deduction),
+    /// Nothing can be proven by the rules of the solver so this is the closed square least likely to be a mine.
+    /// Going through every placement of the mines can still show it to be safe
+    Guess { index: IndexType, probability: f64 },
+}
+
+impl Hint {
+    /// Find a hint for the board using only what the player can see.
+    /// A provably safe square is preferred over a provable mine that is not flagged yet
+    /// and the least risky square is only suggested when nothing can be proven.
+    /// Returns `None` if the game is over or the chances of the mines can not be worked out
+    pub fn find(board: &Board) -> Option<Self> {
+        Self::find_cancellable(board, &AtomicBool::new(false))
+    }
+
+    /// The same as `find` but it stops and returns `None` when `cancel` is set.
+    /// Working out the least risky square can take long on a large board
+    pub fn find_cancellable(board: &Board, cancel: &AtomicBool) -> Option<Self> {
+        if board.status() != Status::InGame {
+            return None;
+        }
+
+        if !board.is_generated() {
+            let (width, height) = board.size();
+            let center = board.point_to_index(cgmath::vec2(width as i32 / 2, height as i32 / 2));
+            return Some(Hint::FirstSquare(center));
+        }
+
+        let mut solver = Solver::new();
+        let mut mine = None;
+        loop {
+            // The order of the deductions is not fixed so the lowest square is picked
+            let mut deductions = solver.deduce(board);
+            deductions.sort_unstable_by_key(|deduction| deduction.index);
+
+            if let Some(safe) = deductions.iter().find(|deduction| !deduction.is_mine) {
+                return Some(Hint::Deduction(*safe));
+            }
+
+            if deductions.is_empty() {
+                break;
+            }
+
+            // Mines found now can prove squares safe in the next round
+            if mine.is_none() {
+                mine = deductions
+                    .iter()
+                    .find(|deduction| {
+                        board.square(deduction.index) != &SquareState::Closed(Mark::Flag)
+                    })
+                    .copied();
+            }
+            for deduction in deductions {
+                solver.mark_mine(deduction.index);
+            }
+        }
+
+        if let Some(mine) = mine {
+            return Some(Hint::Deduction(mine));
+        }
+
+        let probabilities =
+            probability::cancellable_mine_probabilities(board, solver.known_mines(), cancel)
+                .ok()?;
+        if cancel.load(atomic::Ordering::Relaxed) {
+            return None;
+        }
+        probabilities
+            .iter()
+            .enumerate()
+            .filter(|(index, _)| !solver.known_mines().contains(index))
+            .filter_map(|(index, probability)| probability.map(|p| (index, p)))
+            .min_by(|a, b| a.1.partial_cmp(&b.1).unwrap_or(Ordering::Equal))
+            .map(|(index, probability)| Hint::Guess { index, probability })
+    }
+
+    /// The square the hint points to
+    pub fn index(&self) -> IndexType {
+        match self {
+            Hint::FirstSquare(index) => *index,
+            Hint::Deduction(deduction) => deduction.index,
+            Hint::Guess { index, .. } => *index,
+        }
+    }
+
+    /// Why the square is safe, a mine or the best guess
+    pub fn explanation(&self) -> String {
+        match self {
+            Hint::FirstSquare(_) => "Safe: the first square opened is never a mine".to_string(),
+            Hint::Deduction(Deduction {
+                is_mine: false,
+                rule,
+                ..
+            }) => format!("Safe: {}", Self::rule_explanation(*rule, false)),
+            Hint::Deduction(Deduction {
+                is_mine: true,
+                rule,
+                ..
+            }) => format!("Mine: {}", Self::rule_explanation(*rule, true)),
+            Hint::Guess { probability, .. } if *probability == 0.0 => {
+                "Safe: no way of placing the mines left puts one here".to_string()
+            }
+            Hint::Guess { probability, .. } => format!(
+                "Nothing can be proven, this square has the lowest chance of a mine: {:.0}%",
+                probability * 100.0
+            ),
+        }
+    }
+
+    fn rule_explanation(rule: Rule, is_mine: bool) -> &'static str {
+        match (rule, is_mine) {
+            (Rule::SingleSquare, false) => "a number next to it already has all of its mines",
+            (Rule::SingleSquare, true) => {
+                "a number next to it has as many closed squares as mines left"
+            }
+            (Rule::Subset, _) => {
+                "the squares of one number are all next to another number and the mines left over decide it"
+            }
+            (Rule::Overlap, _) => {
+                "two numbers share squares and the mines the shared squares can hold decide it"
+            }
+            (Rule::MineCount, _) => "the amount of mines left on the board decides it",
+        }
+    }
+}
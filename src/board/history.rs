@@ -0,0 +1,151 @@
+use serde::{Deserialize, Serialize};
+
+use super::{Board, IndexType, SquareState, Status};
+
+/// A move the player can make on a board
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum Move {
+    Reveal(IndexType),
+    Flag(IndexType),
+    Chord(IndexType),
+}
+
+impl Move {
+    /// Make the move on the board
+    pub fn apply(&self, board: &mut Board) {
+        match *self {
+            Move::Reveal(index) => board.reveal(index),
+            Move::Flag(index) => board.toggle_flag(index),
+            Move::Chord(index) => board.chord(index),
+        }
+    }
+}
+
+/// A move together with what it changed on the board
+#[derive(Serialize, Deserialize, Clone, Debug)]
+struct Step {
+    player_move: Move,
+    /// The squares the move changed as they were before it
+    squares: Vec<(IndexType, SquareState)>,
+    status: Status,
+    detonated: Option<IndexType>,
+    /// Wether the move generated the mines
+    generated: bool,
+}
+
+impl Step {
+    /// Make the move on the board and remember what it changed.
+    /// Returns none if the move did not change the board
+    fn apply(board: &mut Board, player_move: Move) -> Option<Self> {
+        let before = board.squares().to_vec();
+        let status = board.status();
+        let detonated = board.detonated();
+        let generated = board.is_generated();
+
+        player_move.apply(board);
+
+        let squares: Vec<(IndexType, SquareState)> = before
+            .into_iter()
+            .enumerate()
+            .filter(|(index, square)| board.square(*index) != square)
+            .collect();
+
+        if squares.is_empty() && board.status() == status {
+            return None;
+        }
+
+        Some(Step {
+            player_move,
+            squares,
+            status,
+            detonated,
+            generated: !generated && board.is_generated(),
+        })
+    }
+
+    /// Put the board back the way it was before the move
+    fn undo(self, board: &mut Board) {
+        for (index, square) in self.squares {
+            board.restore_square(index, square);
+        }
+        board.restore_status(self.status, self.detonated);
+        if self.generated {
+            board.remove_mines();
+        }
+    }
+}
+
+/// # History
+/// Keeps every move with the squares it changed so moves can be undone and redone.
+/// A reveal that opens up an area is a single move and is undone all at once
+#[derive(Serialize, Deserialize, Clone, Debug, Default)]
+pub struct History {
+    undo: Vec<Step>,
+    redo: Vec<Move>,
+    used_undo: bool,
+}
+
+impl History {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Make a move on the board and remember what it changed.
+    /// Moves that do not change the board are not remembered
+    pub fn apply(&mut self, board: &mut Board, player_move: Move) {
+        if let Some(step) = Step::apply(board, player_move) {
+            self.undo.push(step);
+            self.redo.clear();
+        }
+    }
+
+    /// Returns true if there is a move to undo.
+    /// A lost game can only be undone when `allow_after_loss` is set and a won game can't be undone
+    pub fn can_undo(&self, board: &Board, allow_after_loss: bool) -> bool {
+        let allowed = match board.status() {
+            Status::InGame => true,
+            Status::Lost => allow_after_loss,
+            Status::Won => false,
+        };
+
+        allowed && !self.undo.is_empty()
+    }
+
+    /// Undo the last move. Returns true if a move was undone
+    pub fn undo(&mut self, board: &mut Board, allow_after_loss: bool) -> bool {
+        if !self.can_undo(board, allow_after_loss) {
+            return false;
+        }
+
+        if let Some(step) = self.undo.pop() {
+            self.redo.push(step.player_move);
+            step.undo(board);
+            self.used_undo = true;
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Redo the last undone move by making it again. Returns true if a move was redone
+    pub fn redo(&mut self, board: &mut Board) -> bool {
+        if board.status() != Status::InGame {
+            return false;
+        }
+
+        if let Some(player_move) = self.redo.pop() {
+            if let Some(step) = Step::apply(board, player_move) {
+                self.undo.push(step);
+            }
+            true
+        } else {
+            false
+        }
+    }
+
+    /// Returns true if undo has been used during the game.
+    /// Such games do not count for any records
+    pub fn used_undo(&self) -> bool {
+        self.used_undo
+    }
+}
@@ -0,0 +1,250 @@
+use std::{collections::BTreeSet, convert::TryFrom, fs, path::Path};
+
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    err_here,
+    error::{LocatedError, WrappedError},
+};
+
+use super::{Board, IndexType};
+
+/// # Layout
+/// Where the mines are on a board, used to play boards from somewhere else and to share generated ones.
+///
+/// Layouts are read and written as text with the size on the first line and a row of `*` for mines
+/// and `.` for safe squares on every line after it:
+/// ```text
+/// 3x2
+/// .*.
+/// ..*
+/// ```
+/// or in the minesweeper board format (MBF), one byte for the width and the height, two bytes for the
+/// amount of mines and then one byte for the x and the y of every mine
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(try_from = "RawLayout")]
+pub struct Layout {
+    size: (usize, usize),
+    mines: BTreeSet<IndexType>,
+}
+
+/// A layout as it is read from replays and saves before it is checked
+#[derive(Deserialize)]
+struct RawLayout {
+    size: (usize, usize),
+    mines: BTreeSet<IndexType>,
+}
+
+impl TryFrom<RawLayout> for Layout {
+    type Error = String;
+
+    fn try_from(raw: RawLayout) -> Result<Self, Self::Error> {
+        let layout = Layout::new(raw.size, raw.mines)?;
+        layout.validate()?;
+        Ok(layout)
+    }
+}
+
+impl Layout {
+    const MINE: char = '*';
+    const SAFE: char = '.';
+
+    /// A layout with mines on the given squares, the error says which mine is outside of the board
+    pub fn new(
+        size: (usize, usize),
+        mines: impl IntoIterator<Item = IndexType>,
+    ) -> Result<Self, String> {
+        let mines: BTreeSet<IndexType> = mines.into_iter().collect();
+        if let Some(mine) = mines.iter().find(|mine| **mine >= size.0 * size.1) {
+            return Err(format!(
+                "The mine on square {} is outside of the {}x{} board",
+                mine, size.0, size.1
+            ));
+        }
+
+        Ok(Layout { size, mines })
+    }
+
+    /// The layout of the board, `None` if its mines have not been generated yet
+    pub fn from_board(board: &Board) -> Option<Self> {
+        if !board.is_generated() {
+            return None;
+        }
+
+        Self::new(board.size(), board.mines().iter().copied()).ok()
+    }
+
+    /// Checks that the layout can be played, the error says why it can not.
+    /// Any size works since the window fits itself to the board
+    pub fn validate(&self) -> Result<(), String> {
+        let (width, height) = self.size;
+        if width == 0 || height == 0 {
+            return Err(format!("A {}x{} board has no squares", width, height));
+        }
+
+        if self.mines.len() >= width * height {
+            return Err("There must be at least 1 safe square".to_string());
+        }
+
+        Ok(())
+    }
+
+    /// The width and height of the board in squares
+    pub fn size(&self) -> (usize, usize) {
+        self.size
+    }
+
+    /// The squares with mines in increasing order
+    pub fn mines(&self) -> impl Iterator<Item = IndexType> + '_ {
+        self.mines.iter().copied()
+    }
+
+    pub fn number_of_mines(&self) -> usize {
+        self.mines.len()
+    }
+
+    /// Read a layout from text, the error says what is wrong with it
+    pub fn from_text(text: &str) -> Result<Self, String> {
+        let mut lines = text.lines().map(str::trim).filter(|line| !line.is_empty());
+
+        let header = lines.next().ok_or("The board is empty")?;
+        let size = header
+            .split_once('x')
+            .and_then(|(width, height)| Some((width.parse().ok()?, height.parse().ok()?)))
+            .ok_or_else(|| format!("{} is not a size like 9x9", header))?;
+        let (width, height) = size;
+
+        let mut mines = vec![];
+        let mut rows = 0;
+        for (y, line) in lines.enumerate() {
+            if y == height {
+                return Err(format!("There are more than {} rows", height));
+            }
+            if line.chars().count() != width {
+                return Err(format!(
+                    "Row {} has {} squares instead of {}",
+                    y + 1,
+                    line.chars().count(),
+                    width
+                ));
+            }
+
+            for (x, square) in line.chars().enumerate() {
+                match square {
+                    Self::MINE => mines.push(x + y * width),
+                    Self::SAFE => {}
+                    _ => return Err(format!("{} in row {} is not * or .", square, y + 1)),
+                }
+            }
+            rows += 1;
+        }
+
+        if rows != height {
+            return Err(format!("There are {} rows instead of {}", rows, height));
+        }
+
+        Self::new(size, mines)
+    }
+
+    /// Write the layout as text
+    pub fn to_text(&self) -> String {
+        let (width, height) = self.size;
+        let mut text = format!("{}x{}\n", width, height);
+        for y in 0..height {
+            text.extend((0..width).map(|x| {
+                if self.mines.contains(&(x + y * width)) {
+                    Self::MINE
+                } else {
+                    Self::SAFE
+                }
+            }));
+            text.push('\n');
+        }
+        text
+    }
+
+    /// Read a layout in the minesweeper board format, the error says what is wrong with it
+    pub fn from_mbf(bytes: &[u8]) -> Result<Self, String> {
+        if bytes.len() < 4 {
+            return Err("The board is too short to have a size".to_string());
+        }
+
+        let (width, height) = (bytes[0] as usize, bytes[1] as usize);
+        let number_of_mines = u16::from_be_bytes([bytes[2], bytes[3]]) as usize;
+        let positions = &bytes[4..];
+        if positions.len() != number_of_mines * 2 {
+            return Err(format!(
+                "The board has {} bytes of mines instead of {}",
+                positions.len(),
+                number_of_mines * 2
+            ));
+        }
+
+        let mut mines = BTreeSet::new();
+        for position in positions.chunks(2) {
+            let (x, y) = (position[0] as usize, position[1] as usize);
+            if x >= width || y >= height {
+                return Err(format!("The mine at {}, {} is outside of the board", x, y));
+            }
+            if !mines.insert(x + y * width) {
+                return Err(format!("There are two mines at {}, {}", x, y));
+            }
+        }
+
+        Ok(Layout {
+            size: (width, height),
+            mines,
+        })
+    }
+
+    /// Write the layout in the minesweeper board format.
+    /// Fails if the board is too large for the format
+    pub fn to_mbf(&self) -> Result<Vec<u8>, String> {
+        let (width, height) = self.size;
+        if width > u8::MAX as usize || height > u8::MAX as usize {
+            return Err(format!("A {}x{} board is too large", width, height));
+        }
+
+        let mut bytes = vec![width as u8, height as u8];
+        bytes.extend_from_slice(&(self.mines.len() as u16).to_be_bytes());
+        for mine in &self.mines {
+            bytes.push((mine % width) as u8);
+            bytes.push((mine / width) as u8);
+        }
+        Ok(bytes)
+    }
+
+    /// Returns true if the path is for the minesweeper board format, otherwise it is text
+    fn is_mbf(path: &Path) -> bool {
+        path.extension()
+            .is_some_and(|extension| extension.eq_ignore_ascii_case("mbf"))
+    }
+
+    /// Load a layout from a file, files ending in `.mbf` are read in the minesweeper board format.
+    /// Layouts that can not be played are an error as well
+    pub fn load(path: &Path) -> Result<Self, LocatedError> {
+        let layout = if Self::is_mbf(path) {
+            Self::from_mbf(&fs::read(path).map_err(err_here!())?)
+        } else {
+            Self::from_text(&fs::read_to_string(path).map_err(err_here!())?)
+        };
+
+        layout
+            .and_then(|layout| layout.validate().map(|_| layout))
+            .map_err(|e| WrappedError::LayoutError(format!("{:?}: {}", path, e)))
+            .map_err(err_here!())
+    }
+
+    /// Write the layout to a file in the format picked by the extension the same way it is loaded
+    pub fn write(&self, path: &Path) -> Result<(), LocatedError> {
+        let bytes = if Self::is_mbf(path) {
+            self.to_mbf()
+                .map_err(WrappedError::LayoutError)
+                .map_err(err_here!())?
+        } else {
+            self.to_text().into_bytes()
+        };
+
+        fs::write(path, bytes).map_err(err_here!())
+    }
+}
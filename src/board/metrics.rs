@@ -0,0 +1,79 @@
+use std::time::Duration;
+
+use serde::{Deserialize, Serialize};
+
+use super::{Board, Move};
+
+/// The clicks made during a game by the kind of move they made
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Clicks {
+    pub left: u32,
+    pub right: u32,
+    pub chord: u32,
+}
+
+impl Clicks {
+    /// Count the click that made the move, reveals are left clicks and flags are right clicks
+    pub fn add(&mut self, player_move: Move) {
+        match player_move {
+            Move::Reveal(_) => self.left += 1,
+            Move::Flag(_) => self.right += 1,
+            Move::Chord(_) => self.chord += 1,
+        }
+    }
+
+    pub fn total(&self) -> u32 {
+        self.left + self.right + self.chord
+    }
+}
+
+/// # Metrics
+/// How fast and how efficiently a game was played
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct Metrics {
+    pub time: Duration,
+    /// The 3BV of the whole board
+    pub three_bv: usize,
+    /// The part of the 3BV that was opened, the same as the 3BV for a won game
+    pub solved_three_bv: usize,
+    pub clicks: Clicks,
+}
+
+impl Metrics {
+    pub fn new(board: &Board, clicks: Clicks, time: Duration) -> Self {
+        Metrics {
+            time,
+            three_bv: board.three_bv(),
+            solved_three_bv: board.solved_three_bv(),
+            clicks,
+        }
+    }
+
+    /// The solved 3BV per second
+    pub fn three_bv_per_second(&self) -> f32 {
+        let seconds = self.time.as_secs_f32();
+        if seconds > 0.0 {
+            self.solved_three_bv as f32 / seconds
+        } else {
+            0.0
+        }
+    }
+
+    /// The solved 3BV as a percentage of the clicks made.
+    /// A perfect game is 100% and extra or wasted clicks lower it
+    pub fn efficiency(&self) -> f32 {
+        self.per_click(self.solved_three_bv) * 100.0
+    }
+
+    /// The index of efficiency, the 3BV of the whole board per click made
+    pub fn ioe(&self) -> f32 {
+        self.per_click(self.three_bv)
+    }
+
+    fn per_click(&self, value: usize) -> f32 {
+        match self.clicks.total() {
+            0 => 0.0,
+            clicks => value as f32 / clicks as f32,
+        }
+    }
+}
@@ -0,0 +1,16 @@
+pub mod bot;
+mod game_board;
+mod hint;
+mod history;
+mod layout;
+mod metrics;
+pub mod probability;
+pub mod solver;
+mod topology;
+
+pub use game_board::*;
+pub use hint::Hint;
+pub use history::{History, Move};
+pub use layout::Layout;
+pub use metrics::{Clicks, Metrics};
+pub use topology::Topology;
@@ -0,0 +1,370 @@
+use std::{
+    collections::{HashMap, HashSet},
+    sync::atomic::{AtomicBool, Ordering},
+};
+
+use super::{
+    solver::{Constraint, Solver},
+    Board, IndexType,
+};
+
+/// Why the chances of the mines could not be worked out
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ProbabilityError {
+    /// No placement of the mines agrees with the board, which happens when the known mines are wrong
+    NoPlacement,
+    /// The squares next to the numbers can hold the mines in too many ways to go through all of them
+    TooManyCombinations,
+    /// The cancel flag was set before the chances were worked out
+    Cancelled,
+}
+
+/// The ways mines can be placed on a group of squares that are tied together by numbers
+struct Group {
+    squares: Vec<IndexType>,
+    /// `solutions[k]` is the amount of ways to place `k` mines in the group
+    solutions: Vec<f64>,
+    /// `mine_counts[k][i]` is how many of the ways with `k` mines have a mine on `squares[i]`
+    mine_counts: Vec<Vec<f64>>,
+}
+
+/// The numbers around the squares of a group while the mines are placed one square at a time
+struct Search<'a> {
+    /// The constraints of every square as indices into `targets`
+    square_constraints: &'a [Vec<usize>],
+    /// The amount of mines every constraint needs
+    targets: &'a [usize],
+    /// The mines placed around every constraint so far
+    placed: Vec<usize>,
+    /// The squares around every constraint that are not decided yet
+    open: Vec<usize>,
+    /// The most mines the group can hold
+    max_mines: usize,
+    mines: Vec<bool>,
+    /// The amount of mines placed so far
+    mine_count: usize,
+    /// Set from another thread to stop the search
+    cancel: &'a AtomicBool,
+    /// The amount of squares tried so far, the cancel flag is checked every `CANCEL_CHECK` steps
+    steps: usize,
+    /// Set when the search gave up before every placement was found
+    stopped: Option<ProbabilityError>,
+}
+
+impl Search<'_> {
+    const CANCEL_CHECK: usize = 4096;
+
+    /// The most squares tried for one group before giving up, a few seconds at most
+    const MAX_STEPS: usize = 1 << 22;
+
+    /// Try both a mine and no mine on the square at `position` and every square after it
+    fn place(&mut self, position: usize, group: &mut Group) {
+        self.steps += 1;
+        if self.steps.is_multiple_of(Self::CANCEL_CHECK) && self.cancel.load(Ordering::Relaxed) {
+            self.stopped = Some(ProbabilityError::Cancelled);
+        } else if self.steps > Self::MAX_STEPS {
+            self.stopped = Some(ProbabilityError::TooManyCombinations);
+        }
+        if self.stopped.is_some() {
+            return;
+        }
+
+        if position == self.mines.len() {
+            let k = self.mine_count;
+            group.solutions[k] += 1.0;
+            for (i, mine) in self.mines.iter().enumerate() {
+                if *mine {
+                    group.mine_counts[k][i] += 1.0;
+                }
+            }
+            return;
+        }
+
+        for &mine in &[false, true] {
+            if mine && self.mine_count == self.max_mines {
+                continue;
+            }
+
+            // Every number has to be able to get exactly its mines from the squares left around it
+            let fits = self.square_constraints[position].iter().all(|&c| {
+                let placed = self.placed[c] + mine as usize;
+                placed <= self.targets[c] && placed + self.open[c] > self.targets[c]
+            });
+            if !fits {
+                continue;
+            }
+
+            for &c in &self.square_constraints[position] {
+                self.placed[c] += mine as usize;
+                self.open[c] -= 1;
+            }
+            self.mines[position] = mine;
+            self.mine_count += mine as usize;
+
+            self.place(position + 1, group);
+
+            for &c in &self.square_constraints[position] {
+                self.placed[c] -= mine as usize;
+                self.open[c] += 1;
+            }
+            self.mines[position] = false;
+            self.mine_count -= mine as usize;
+        }
+    }
+}
+
+/// The natural logarithm of the binomial coefficient, `ln_factorials[n]` has to be ln(n!)
+fn ln_choose(ln_factorials: &[f64], n: usize, k: usize) -> f64 {
+    ln_factorials[n] - ln_factorials[k] - ln_factorials[n - k]
+}
+
+/// The amount of ways to combine the solutions of the groups by the total amount of mines
+fn combine(a: &[f64], b: &[f64]) -> Vec<f64> {
+    let mut result = vec![0.0; a.len() + b.len() - 1];
+    for (i, x) in a.iter().enumerate() {
+        for (j, y) in b.iter().enumerate() {
+            result[i + j] += x * y;
+        }
+    }
+    result
+}
+
+/// Split the squares next to numbers into groups where the squares of different groups never share a number
+fn groups(constraints: &[Constraint]) -> Vec<(Vec<IndexType>, Vec<Constraint>)> {
+    let mut by_square: HashMap<IndexType, Vec<usize>> = HashMap::new();
+    for (i, constraint) in constraints.iter().enumerate() {
+        for &square in &constraint.squares {
+            by_square.entry(square).or_default().push(i);
+        }
+    }
+
+    let mut visited = HashSet::new();
+    let mut groups = vec![];
+
+    // Go through the constraints in order so the result does not depend on hashing
+    for constraint in constraints {
+        let start = constraint.squares[0];
+        if visited.contains(&start) {
+            continue;
+        }
+
+        let mut group_squares = vec![];
+        let mut group_constraints = HashSet::new();
+        let mut to_visit = vec![start];
+        visited.insert(start);
+
+        // A search in the order squares are found keeps squares of the same number close together
+        let mut next = 0;
+        while next < to_visit.len() {
+            let square = to_visit[next];
+            next += 1;
+            group_squares.push(square);
+
+            for &c in &by_square[&square] {
+                group_constraints.insert(c);
+                for &other in &constraints[c].squares {
+                    if visited.insert(other) {
+                        to_visit.push(other);
+                    }
+                }
+            }
+        }
+
+        let mut group_constraints: Vec<usize> = group_constraints.into_iter().collect();
+        group_constraints.sort_unstable();
+        groups.push((
+            group_squares,
+            group_constraints
+                .into_iter()
+                .map(|c| constraints[c].clone())
+                .collect(),
+        ));
+    }
+
+    groups
+}
+
+/// Find every way to place mines on the squares of a group that agrees with its numbers.
+/// Fails if the search was cancelled or there are too many ways to go through
+fn solve_group(
+    squares: Vec<IndexType>,
+    constraints: &[Constraint],
+    max_mines: usize,
+    cancel: &AtomicBool,
+) -> Result<Group, ProbabilityError> {
+    let position: HashMap<IndexType, usize> =
+        squares.iter().enumerate().map(|(i, s)| (*s, i)).collect();
+
+    let mut square_constraints = vec![vec![]; squares.len()];
+    for (c, constraint) in constraints.iter().enumerate() {
+        for square in &constraint.squares {
+            square_constraints[position[square]].push(c);
+        }
+    }
+
+    let targets: Vec<usize> = constraints
+        .iter()
+        .map(|constraint| constraint.mines)
+        .collect();
+    let max_mines = max_mines.min(squares.len());
+
+    let mut group = Group {
+        solutions: vec![0.0; max_mines + 1],
+        mine_counts: vec![vec![0.0; squares.len()]; max_mines + 1],
+        squares,
+    };
+
+    let mut search = Search {
+        square_constraints: &square_constraints,
+        targets: &targets,
+        placed: vec![0; constraints.len()],
+        open: constraints
+            .iter()
+            .map(|constraint| constraint.squares.len())
+            .collect(),
+        max_mines,
+        mines: vec![false; group.squares.len()],
+        mine_count: 0,
+        cancel,
+        steps: 0,
+        stopped: None,
+    };
+    search.place(0, &mut group);
+
+    match search.stopped {
+        Some(error) => Err(error),
+        None => Ok(group),
+    }
+}
+
+/// # Mine Probabilities
+/// The chance of a mine under every closed square from what the player can see.
+/// The numbers, the total amount of mines and the `known_mines` are taken into account by
+/// going through every way the mines can be placed next to the numbers, weighted by the
+/// ways the rest of the mines can be spread over the squares that are not next to any number.
+///
+/// Open squares are `None` and known mines are certain.
+/// Fails if no placement of the mines agrees with the board or there are too many placements to go through
+pub fn mine_probabilities(
+    board: &Board,
+    known_mines: &HashSet<IndexType>,
+) -> Result<Vec<Option<f64>>, ProbabilityError> {
+    cancellable_mine_probabilities(board, known_mines, &AtomicBool::new(false))
+}
+
+/// The same as `mine_probabilities` but it stops when `cancel` is set.
+/// Used to work out the probabilities on another thread without waiting for an outdated board
+pub fn cancellable_mine_probabilities(
+    board: &Board,
+    known_mines: &HashSet<IndexType>,
+    cancel: &AtomicBool,
+) -> Result<Vec<Option<f64>>, ProbabilityError> {
+    let mut solver = Solver::new();
+    for &mine in known_mines {
+        solver.mark_mine(mine);
+    }
+
+    let unknown = solver.unknown_squares(board);
+    let mines_left = board
+        .number_of_mines()
+        .checked_sub(known_mines.len())
+        .ok_or(ProbabilityError::NoPlacement)?;
+
+    let constraints = solver.constraints(board);
+
+    let groups: Vec<Group> = groups(&constraints)
+        .into_iter()
+        .map(|(squares, group_constraints)| {
+            solve_group(squares, &group_constraints, mines_left, cancel)
+        })
+        .collect::<Result<_, _>>()?;
+
+    let next_to_numbers: usize = groups.iter().map(|group| group.squares.len()).sum();
+    let others = unknown.len() - next_to_numbers;
+
+    // The ways to place the mines next to numbers by how many mines are placed there
+    let all = groups
+        .iter()
+        .fold(vec![1.0], |ways, group| combine(&ways, &group.solutions));
+
+    // The ways to spread the rest of the mines over the other squares, scaled to stay in range
+    let mut ln_factorials = vec![0.0; unknown.len() + 1];
+    for n in 1..ln_factorials.len() {
+        ln_factorials[n] = ln_factorials[n - 1] + (n as f64).ln();
+    }
+    let ln_rest: Vec<Option<f64>> = (0..all.len())
+        .map(|k| {
+            mines_left
+                .checked_sub(k)
+                .filter(|rest| *rest <= others)
+                .map(|rest| ln_choose(&ln_factorials, others, rest))
+        })
+        .collect();
+    let largest = ln_rest
+        .iter()
+        .flatten()
+        .copied()
+        .fold(f64::NEG_INFINITY, f64::max);
+    let rest: Vec<f64> = ln_rest
+        .iter()
+        .map(|ln| ln.map_or(0.0, |ln| (ln - largest).exp()))
+        .collect();
+
+    let total: f64 = all.iter().zip(&rest).map(|(ways, rest)| ways * rest).sum();
+    if total <= 0.0 {
+        return Err(ProbabilityError::NoPlacement);
+    }
+
+    let mut probabilities = vec![None; board.len()];
+    for &mine in known_mines {
+        probabilities[mine] = Some(1.0);
+    }
+
+    for (g, group) in groups.iter().enumerate() {
+        let without = groups
+            .iter()
+            .enumerate()
+            .filter(|(other, _)| *other != g)
+            .fold(vec![1.0], |ways, (_, other)| {
+                combine(&ways, &other.solutions)
+            });
+
+        // The weight of every way to place k mines in this group
+        let weights: Vec<f64> = (0..group.solutions.len())
+            .map(|k| {
+                without
+                    .iter()
+                    .enumerate()
+                    .filter_map(|(r, ways)| rest.get(k + r).map(|rest| ways * rest))
+                    .sum()
+            })
+            .collect();
+
+        for (i, &square) in group.squares.iter().enumerate() {
+            let mine_weight: f64 = (0..group.solutions.len())
+                .map(|k| group.mine_counts[k][i] * weights[k])
+                .sum();
+            probabilities[square] = Some(mine_weight / total);
+        }
+    }
+
+    if others > 0 {
+        let mines_in_others: f64 = all
+            .iter()
+            .zip(&rest)
+            .enumerate()
+            .map(|(k, (ways, rest))| ways * rest * mines_left.saturating_sub(k) as f64)
+            .sum();
+        let probability = mines_in_others / total / others as f64;
+
+        let grouped: HashSet<IndexType> = groups
+            .iter()
+            .flat_map(|group| group.squares.iter().copied())
+            .collect();
+        for &square in unknown.iter().filter(|s| !grouped.contains(s)) {
+            probabilities[square] = Some(probability);
+        }
+    }
+
+    Ok(probabilities)
+}
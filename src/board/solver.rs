@@ -0,0 +1,266 @@
+use std::collections::{BTreeSet, HashMap, HashSet};
+
+use super::{Board, IndexType, SquareState, Status};
+
+/// The rule the solver used to make a deduction
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Rule {
+    /// A single number has either no mines left around it or as many mines as closed squares
+    SingleSquare,
+    /// The closed squares around one number are all around another number as well
+    Subset,
+    /// Two numbers share some of their closed squares
+    Overlap,
+    /// The amount of mines left on the board decides the squares
+    MineCount,
+}
+
+/// Something the solver has proven about a closed square
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct Deduction {
+    pub index: IndexType,
+    pub is_mine: bool,
+    pub rule: Rule,
+}
+
+/// A set of closed squares that contain exactly `mines` mines
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub(super) struct Constraint {
+    pub(super) squares: Vec<IndexType>,
+    pub(super) mines: usize,
+}
+
+/// # Solver
+/// Deduces safe squares and mines using only what a player can see on the board,
+/// that is the open numbers, the total amount of mines and the mines it has deduced before.
+/// Flags are not trusted since the player can place them wrong.
+#[derive(Clone, Debug, Default)]
+pub struct Solver {
+    known_mines: HashSet<IndexType>,
+}
+
+impl Solver {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// The mines the solver has deduced so far
+    pub fn known_mines(&self) -> &HashSet<IndexType> {
+        &self.known_mines
+    }
+
+    /// Remember a deduced mine so it can be used in later deductions
+    pub fn mark_mine(&mut self, index: IndexType) {
+        self.known_mines.insert(index);
+    }
+
+    /// Finds squares that can be proven to be safe or to be mines.
+    /// The simplest rule that gives any deductions is used, so an empty result means the board
+    /// can not be progressed without guessing.
+    pub fn deduce(&self, board: &Board) -> Vec<Deduction> {
+        let constraints = self.constraints(board);
+
+        let mut deductions = Vec::new();
+        for constraint in &constraints {
+            Self::single_square(constraint, Rule::SingleSquare, &mut deductions);
+        }
+
+        if deductions.is_empty() {
+            Self::pairs(&constraints, &mut deductions);
+        }
+
+        if deductions.is_empty() {
+            self.mine_count(board, &constraints, &mut deductions);
+        }
+
+        let mut seen = HashSet::new();
+        deductions.retain(|d| seen.insert(d.index));
+        deductions
+    }
+
+    /// Squares that are closed and not known to be mines
+    pub(super) fn unknown_squares(&self, board: &Board) -> Vec<IndexType> {
+        (0..board.len())
+            .filter(|i| {
+                matches!(board.square(*i), SquareState::Closed(_)) && !self.known_mines.contains(i)
+            })
+            .collect()
+    }
+
+    /// Build a constraint for every open number that still has unknown squares around it.
+    /// Numbers with the same unknown squares and mines give a single constraint
+    pub(super) fn constraints(&self, board: &Board) -> Vec<Constraint> {
+        let mut constraints = HashSet::new();
+
+        for (index, square) in board.squares().iter().enumerate() {
+            let count = match square {
+                SquareState::Open(count) if !board.is_mine(index) => *count as usize,
+                _ => continue,
+            };
+
+            let mut squares = Vec::new();
+            let mut mines = count;
+            for &neighbor in board.get_neighbors(index).iter().flatten() {
+                if self.known_mines.contains(&neighbor) {
+                    mines = mines.saturating_sub(1);
+                } else if let SquareState::Closed(_) = board.square(neighbor) {
+                    squares.push(neighbor);
+                }
+            }
+
+            if !squares.is_empty() {
+                squares.sort_unstable();
+                constraints.insert(Constraint { squares, mines });
+            }
+        }
+
+        // Sorted so the deductions and the rules they are made with do not depend on the hash order
+        let mut constraints: Vec<Constraint> = constraints.into_iter().collect();
+        constraints.sort_unstable();
+        constraints
+    }
+
+    /// Apply the single square rule on a constraint.
+    /// No mines left means all squares are safe and as many mines as squares means all are mines
+    fn single_square(constraint: &Constraint, rule: Rule, deductions: &mut Vec<Deduction>) {
+        let is_mine = if constraint.mines == 0 {
+            false
+        } else if constraint.mines == constraint.squares.len() {
+            true
+        } else {
+            return;
+        };
+
+        deductions.extend(constraint.squares.iter().map(|&index| Deduction {
+            index,
+            is_mine,
+            rule,
+        }));
+    }
+
+    /// Compare every pair of constraints that share squares
+    fn pairs(constraints: &[Constraint], deductions: &mut Vec<Deduction>) {
+        let mut by_square: HashMap<IndexType, Vec<usize>> = HashMap::new();
+        for (i, constraint) in constraints.iter().enumerate() {
+            for &square in &constraint.squares {
+                by_square.entry(square).or_default().push(i);
+            }
+        }
+
+        for (i, a) in constraints.iter().enumerate() {
+            let others: BTreeSet<usize> = a
+                .squares
+                .iter()
+                .flat_map(|square| by_square[square].iter().copied())
+                .filter(|&j| j > i)
+                .collect();
+
+            for j in others {
+                let b = &constraints[j];
+                Self::compare(a, b, None, deductions);
+                Self::compare(b, a, None, deductions);
+            }
+        }
+    }
+
+    /// Compare the constraints with a constraint covering all unknown squares and all the mines left
+    fn mine_count(
+        &self,
+        board: &Board,
+        constraints: &[Constraint],
+        deductions: &mut Vec<Deduction>,
+    ) {
+        let unknown = self.unknown_squares(board);
+        let mines_left = board
+            .number_of_mines()
+            .saturating_sub(self.known_mines.len());
+        let everything = Constraint {
+            squares: unknown,
+            mines: mines_left,
+        };
+
+        Self::single_square(&everything, Rule::MineCount, deductions);
+        if !deductions.is_empty() {
+            return;
+        }
+
+        for constraint in constraints {
+            Self::compare(&everything, constraint, Some(Rule::MineCount), deductions);
+        }
+    }
+
+    /// Look at the squares in `a` that are not in `b`.
+    /// The amount of mines in the shared squares is limited by both constraints which can
+    /// decide all of the squares only in `a`.
+    fn compare(
+        a: &Constraint,
+        b: &Constraint,
+        rule: Option<Rule>,
+        deductions: &mut Vec<Deduction>,
+    ) {
+        let b_squares: HashSet<&IndexType> = b.squares.iter().collect();
+        let only_a: Vec<IndexType> = a
+            .squares
+            .iter()
+            .filter(|s| !b_squares.contains(s))
+            .copied()
+            .collect();
+
+        if only_a.is_empty() {
+            return;
+        }
+
+        let shared = a.squares.len() - only_a.len();
+        let only_b = b.squares.len() - shared;
+
+        // The fewest and most mines the shared squares can hold
+        let shared_min = b.mines.saturating_sub(only_b);
+        let shared_max = shared.min(b.mines).min(a.mines);
+
+        let rule = rule.unwrap_or(if shared == b.squares.len() {
+            Rule::Subset
+        } else {
+            Rule::Overlap
+        });
+
+        let is_mine = if a.mines <= shared_min {
+            false
+        } else if a.mines - shared_max == only_a.len() {
+            true
+        } else {
+            return;
+        };
+
+        deductions.extend(only_a.into_iter().map(|index| Deduction {
+            index,
+            is_mine,
+            rule,
+        }));
+    }
+}
+
+/// Plays the board from `start` using only the solver and returns true if every safe square
+/// could be opened without guessing. The mines of the board have to be generated.
+pub fn is_solvable(board: &Board, start: IndexType) -> bool {
+    let mut board = board.clone();
+    let mut solver = Solver::new();
+
+    board.reveal(start);
+
+    while board.status() == Status::InGame && !board.all_safe_revealed() {
+        let deductions = solver.deduce(&board);
+        if deductions.is_empty() {
+            return false;
+        }
+
+        for deduction in deductions {
+            if deduction.is_mine {
+                solver.mark_mine(deduction.index);
+            } else {
+                board.reveal(deduction.index);
+            }
+        }
+    }
+
+    board.all_safe_revealed()
+}
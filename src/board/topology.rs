@@ -0,0 +1,99 @@
+use serde::{Deserialize, Serialize};
+
+/// The offset to a neighbor as x and y
+type Offset = (i32, i32);
+
+/// How the squares of a board are shaped and which squares are neighbors.
+/// The squares are always stored in rows and columns, the topology decides how the rows fit together
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq, Default)]
+pub enum Topology {
+    /// Squares touching at the sides and the corners, 8 neighbors
+    #[default]
+    Square,
+    /// Hexagons with a corner at the top where every odd row is pushed half a hexagon to the right, 6 neighbors
+    Hex,
+    /// Triangles pointing up and down in turn, every triangle sharing a corner is a neighbor, 12 neighbors
+    Triangle,
+}
+
+impl Topology {
+    /// The most neighbors a square can have in any topology
+    pub const MAX_NEIGHBORS: usize = 12;
+
+    const SQUARE: [Offset; 8] = [
+        (-1, -1),
+        (-1, 0),
+        (-1, 1),
+        (0, -1),
+        (0, 1),
+        (1, -1),
+        (1, 0),
+        (1, 1),
+    ];
+    const HEX_EVEN_ROW: [Offset; 6] = [(-1, -1), (0, -1), (-1, 0), (1, 0), (-1, 1), (0, 1)];
+    const HEX_ODD_ROW: [Offset; 6] = [(0, -1), (1, -1), (-1, 0), (1, 0), (0, 1), (1, 1)];
+    /// One corner above, two corners below
+    const TRIANGLE_UP: [Offset; 12] = [
+        (-1, -1),
+        (0, -1),
+        (1, -1),
+        (-2, 0),
+        (-1, 0),
+        (1, 0),
+        (2, 0),
+        (-2, 1),
+        (-1, 1),
+        (0, 1),
+        (1, 1),
+        (2, 1),
+    ];
+    /// Two corners above, one corner below
+    const TRIANGLE_DOWN: [Offset; 12] = [
+        (-2, -1),
+        (-1, -1),
+        (0, -1),
+        (1, -1),
+        (2, -1),
+        (-2, 0),
+        (-1, 0),
+        (1, 0),
+        (2, 0),
+        (-1, 1),
+        (0, 1),
+        (1, 1),
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Topology::Square => "Square",
+            Topology::Hex => "Hex",
+            Topology::Triangle => "Triangle",
+        }
+    }
+
+    /// The most neighbors a square can have, squares at the edges have fewer
+    pub fn max_neighbors(&self) -> usize {
+        match self {
+            Topology::Square => Self::SQUARE.len(),
+            Topology::Hex => Self::HEX_EVEN_ROW.len(),
+            Topology::Triangle => Self::TRIANGLE_UP.len(),
+        }
+    }
+
+    /// The offsets from the square at the point to its neighbors, they can be outside of the board
+    pub fn neighbor_offsets(&self, point: cgmath::Vector2<i32>) -> &'static [Offset] {
+        match self {
+            Topology::Square => &Self::SQUARE,
+            Topology::Hex if point.y % 2 == 0 => &Self::HEX_EVEN_ROW,
+            Topology::Hex => &Self::HEX_ODD_ROW,
+            Topology::Triangle if Self::points_up(point) => &Self::TRIANGLE_UP,
+            Topology::Triangle => &Self::TRIANGLE_DOWN,
+        }
+    }
+
+    /// Returns true if the triangle at the point has a corner at the top.
+    /// The top left triangle points up and every triangle next to it points the other way
+    pub fn points_up(point: cgmath::Vector2<i32>) -> bool {
+        (point.x + point.y) % 2 == 0
+    }
+}
@@ -0,0 +1,55 @@
+use super::GameConfig;
+
+/// # Difficulty
+/// The standard board sizes and amount of mines
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Difficulty {
+    Beginner,
+    Intermediate,
+    Expert,
+}
+
+impl Difficulty {
+    /// All the difficulties from the easiest to the hardest
+    pub const ALL: [Difficulty; 3] = [
+        Difficulty::Beginner,
+        Difficulty::Intermediate,
+        Difficulty::Expert,
+    ];
+
+    /// The name shown in menus
+    pub fn name(&self) -> &'static str {
+        match self {
+            Difficulty::Beginner => "Beginner",
+            Difficulty::Intermediate => "Intermediate",
+            Difficulty::Expert => "Expert",
+        }
+    }
+
+    /// The width and height of the board
+    pub fn game_size(&self) -> (usize, usize) {
+        match self {
+            Difficulty::Beginner => (9, 9),
+            Difficulty::Intermediate => (16, 16),
+            Difficulty::Expert => (30, 16),
+        }
+    }
+
+    /// The amount of mines on the board
+    pub fn number_of_mines(&self) -> usize {
+        match self {
+            Difficulty::Beginner => 10,
+            Difficulty::Intermediate => 40,
+            Difficulty::Expert => 99,
+        }
+    }
+
+    /// Create a copy of the config with the board size and mines of this difficulty
+    pub fn apply(&self, game_config: &GameConfig) -> GameConfig {
+        GameConfig {
+            game_size: self.game_size(),
+            number_of_mines: self.number_of_mines(),
+            ..game_config.clone()
+        }
+    }
+}
@@ -1,8 +1,20 @@
-use serde::Deserialize;
+use std::io::Write;
+
+use ggez::{filesystem, Context};
+use log::info;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Generation, Topology, WinCondition},
+    err_here,
+    error::LocatedError,
+};
+
+use super::InputBindings;
 
 /// # Game Colors
 /// A struct containing the colors used in the game
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GameColors {
     pub square: (u8, u8, u8),
     pub selected_square: (u8, u8, u8),
@@ -11,10 +23,101 @@ pub struct GameColors {
 
 /// # Game Config
 /// The main game config struct loaded from config.ron in resources
-#[derive(Deserialize, Debug, Clone)]
+#[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct GameConfig {
     pub game_size: (usize, usize),
     pub number_of_mines: usize,
     pub square_size: f32,
     pub colors: GameColors,
+    /// The seed used to generate the mines of the first game. Later games and games without it get a random seed
+    #[serde(default)]
+    pub seed: Option<u64>,
+    /// How the mines are placed, classic if it is not set
+    #[serde(default)]
+    pub generation: Generation,
+    /// What the player has to do to win, reveal all safe squares if it is not set
+    #[serde(default)]
+    pub win_condition: WinCondition,
+    /// The shape of the squares, squares if it is not set
+    #[serde(default)]
+    pub topology: Topology,
+    /// In practice mode the move that lost the game can be undone
+    #[serde(default)]
+    pub practice: bool,
+    /// Marking a flagged square puts a question mark on it before the mark is cleared
+    #[serde(default)]
+    pub question_marks: bool,
+    /// The keys and mouse buttons used in a game
+    #[serde(default)]
+    pub bindings: InputBindings,
+}
+
+impl GameConfig {
+    /// The smallest width and height of a board, smaller boards do not fit the hud
+    pub const MIN_SIZE: usize = 8;
+    /// The largest width of a board
+    pub const MAX_WIDTH: usize = 40;
+    /// The largest height of a board
+    pub const MAX_HEIGHT: usize = 24;
+
+    /// The path of the config, a config written to the user config directory is found before the one in resources
+    const PATH: &'static str = "/config.ron";
+
+    /// Load the config
+    pub fn load(ctx: &mut Context) -> Result<Self, LocatedError> {
+        let file = filesystem::open(ctx, Self::PATH).map_err(err_here!())?;
+        ron::de::from_reader(file).map_err(err_here!())
+    }
+
+    /// Write the config to the user config directory
+    pub fn write(&self, ctx: &mut Context) -> Result<(), LocatedError> {
+        let text =
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()).map_err(err_here!())?;
+        let mut file = filesystem::create(ctx, Self::PATH).map_err(err_here!())?;
+        file.write_all(text.as_bytes()).map_err(err_here!())?;
+
+        info!("Saved config to {:?}", filesystem::user_config_dir(ctx));
+        Ok(())
+    }
+
+    /// The amount of squares that are kept free from mines around the first square
+    pub fn safe_squares(&self) -> usize {
+        match self.generation {
+            Generation::Classic => 1,
+            Generation::SafeZone | Generation::NoGuess => 1 + self.topology.max_neighbors(),
+        }
+    }
+
+    /// Check that the board size and the amount of mines makes a playable game.
+    /// The error is a message that can be shown to the player
+    pub fn validate(&self) -> Result<(), String> {
+        let (width, height) = self.game_size;
+
+        if !(Self::MIN_SIZE..=Self::MAX_WIDTH).contains(&width) {
+            return Err(format!(
+                "Width must be {} to {}",
+                Self::MIN_SIZE,
+                Self::MAX_WIDTH
+            ));
+        }
+
+        if !(Self::MIN_SIZE..=Self::MAX_HEIGHT).contains(&height) {
+            return Err(format!(
+                "Height must be {} to {}",
+                Self::MIN_SIZE,
+                Self::MAX_HEIGHT
+            ));
+        }
+
+        if self.number_of_mines == 0 {
+            return Err("There must be at least 1 mine".to_string());
+        }
+
+        let max_mines = (width * height).saturating_sub(self.safe_squares());
+        if self.number_of_mines > max_mines {
+            return Err(format!("At most {} mines fit", max_mines));
+        }
+
+        Ok(())
+    }
 }
@@ -0,0 +1,222 @@
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use serde::{Deserialize, Serialize};
+
+/// Something the player can do in a game with a key or a mouse button
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Action {
+    Reveal,
+    Flag,
+    Chord,
+    Restart,
+    Pause,
+    Undo,
+    Redo,
+    Menu,
+    Hint,
+    Analysis,
+}
+
+impl Action {
+    /// All the actions in the order they are shown in the controls screen
+    pub const ALL: [Action; 10] = [
+        Action::Reveal,
+        Action::Flag,
+        Action::Chord,
+        Action::Restart,
+        Action::Pause,
+        Action::Undo,
+        Action::Redo,
+        Action::Menu,
+        Action::Hint,
+        Action::Analysis,
+    ];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            Action::Reveal => "Reveal",
+            Action::Flag => "Flag",
+            Action::Chord => "Chord",
+            Action::Restart => "Restart",
+            Action::Pause => "Pause",
+            Action::Undo => "Undo",
+            Action::Redo => "Redo",
+            Action::Menu => "Menu",
+            Action::Hint => "Hint",
+            Action::Analysis => "Analysis",
+        }
+    }
+}
+
+/// A key and wether ctrl has to be held with it
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct KeyPress {
+    pub key: KeyCode,
+    #[serde(default)]
+    pub ctrl: bool,
+}
+
+impl KeyPress {
+    pub fn new(key: KeyCode) -> Self {
+        KeyPress { key, ctrl: false }
+    }
+
+    pub fn with_ctrl(key: KeyCode) -> Self {
+        KeyPress { key, ctrl: true }
+    }
+
+    /// Returns true if the key was pressed with the right modifiers
+    pub fn matches(&self, key: KeyCode, keymods: KeyMods) -> bool {
+        self.key == key && self.ctrl == keymods.contains(KeyMods::CTRL)
+    }
+
+    /// The name shown in the controls screen
+    pub fn name(&self) -> String {
+        if self.ctrl {
+            format!("Ctrl+{:?}", self.key)
+        } else {
+            format!("{:?}", self.key)
+        }
+    }
+}
+
+/// The key and mouse button that make an action, either can be left out
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, Default, PartialEq)]
+pub struct Binding {
+    #[serde(default)]
+    pub key: Option<KeyPress>,
+    #[serde(default)]
+    pub mouse: Option<MouseButton>,
+}
+
+impl Binding {
+    fn new(key: Option<KeyPress>, mouse: Option<MouseButton>) -> Self {
+        Binding { key, mouse }
+    }
+}
+
+/// # Input Bindings
+/// The keys and mouse buttons used for every action in a game.
+/// Actions left out of the config keep their default binding
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+#[serde(default)]
+pub struct InputBindings {
+    pub reveal: Binding,
+    pub flag: Binding,
+    pub chord: Binding,
+    pub restart: Binding,
+    pub pause: Binding,
+    pub undo: Binding,
+    pub redo: Binding,
+    pub menu: Binding,
+    pub hint: Binding,
+    pub analysis: Binding,
+}
+
+impl Default for InputBindings {
+    fn default() -> Self {
+        InputBindings {
+            reveal: Binding::new(Some(KeyPress::new(KeyCode::Space)), Some(MouseButton::Left)),
+            flag: Binding::new(Some(KeyPress::new(KeyCode::F)), Some(MouseButton::Right)),
+            chord: Binding::new(Some(KeyPress::new(KeyCode::D)), Some(MouseButton::Middle)),
+            restart: Binding::new(Some(KeyPress::new(KeyCode::F2)), None),
+            pause: Binding::new(Some(KeyPress::new(KeyCode::P)), None),
+            undo: Binding::new(Some(KeyPress::with_ctrl(KeyCode::Z)), None),
+            redo: Binding::new(Some(KeyPress::with_ctrl(KeyCode::Y)), None),
+            menu: Binding::new(Some(KeyPress::new(KeyCode::Back)), None),
+            hint: Binding::new(Some(KeyPress::new(KeyCode::H)), None),
+            analysis: Binding::new(Some(KeyPress::new(KeyCode::Tab)), None),
+        }
+    }
+}
+
+impl InputBindings {
+    /// The keys that move the keyboard cursor and the direction they move it in.
+    /// They can not be rebound but a key bound to an action makes the action instead
+    pub const MOVEMENT_KEYS: [(KeyCode, (i32, i32)); 8] = [
+        (KeyCode::Up, (0, -1)),
+        (KeyCode::I, (0, -1)),
+        (KeyCode::Down, (0, 1)),
+        (KeyCode::K, (0, 1)),
+        (KeyCode::Left, (-1, 0)),
+        (KeyCode::J, (-1, 0)),
+        (KeyCode::Right, (1, 0)),
+        (KeyCode::L, (1, 0)),
+    ];
+
+    /// The direction the key moves the keyboard cursor in
+    pub fn movement(key: KeyCode) -> Option<(i32, i32)> {
+        Self::MOVEMENT_KEYS
+            .iter()
+            .find(|(movement_key, _)| *movement_key == key)
+            .map(|(_, direction)| *direction)
+    }
+
+    pub fn binding(&self, action: Action) -> &Binding {
+        match action {
+            Action::Reveal => &self.reveal,
+            Action::Flag => &self.flag,
+            Action::Chord => &self.chord,
+            Action::Restart => &self.restart,
+            Action::Pause => &self.pause,
+            Action::Undo => &self.undo,
+            Action::Redo => &self.redo,
+            Action::Menu => &self.menu,
+            Action::Hint => &self.hint,
+            Action::Analysis => &self.analysis,
+        }
+    }
+
+    pub fn binding_mut(&mut self, action: Action) -> &mut Binding {
+        match action {
+            Action::Reveal => &mut self.reveal,
+            Action::Flag => &mut self.flag,
+            Action::Chord => &mut self.chord,
+            Action::Restart => &mut self.restart,
+            Action::Pause => &mut self.pause,
+            Action::Undo => &mut self.undo,
+            Action::Redo => &mut self.redo,
+            Action::Menu => &mut self.menu,
+            Action::Hint => &mut self.hint,
+            Action::Analysis => &mut self.analysis,
+        }
+    }
+
+    /// The action made by the key, the first action in `Action::ALL` wins if a key is bound twice
+    pub fn key_action(&self, key: KeyCode, keymods: KeyMods) -> Option<Action> {
+        Action::ALL.iter().copied().find(|action| {
+            self.binding(*action)
+                .key
+                .is_some_and(|press| press.matches(key, keymods))
+        })
+    }
+
+    /// The action made by the mouse button
+    pub fn mouse_action(&self, button: MouseButton) -> Option<Action> {
+        Action::ALL
+            .iter()
+            .copied()
+            .find(|action| self.binding(*action).mouse == Some(button))
+    }
+
+    /// Bind the key to the action and remove it from any other action
+    pub fn bind_key(&mut self, action: Action, press: KeyPress) {
+        for other in Action::ALL.iter() {
+            let binding = self.binding_mut(*other);
+            if binding.key == Some(press) {
+                binding.key = None;
+            }
+        }
+        self.binding_mut(action).key = Some(press);
+    }
+
+    /// Bind the mouse button to the action and remove it from any other action
+    pub fn bind_mouse(&mut self, action: Action, button: MouseButton) {
+        for other in Action::ALL.iter() {
+            let binding = self.binding_mut(*other);
+            if binding.mouse == Some(button) {
+                binding.mouse = None;
+            }
+        }
+        self.binding_mut(action).mouse = Some(button);
+    }
+}
@@ -1,3 +1,7 @@
+mod difficulty;
 mod game_config;
+mod input_bindings;
 
-pub use game_config::GameConfig;
+pub use difficulty::Difficulty;
+pub use game_config::{GameColors, GameConfig};
+pub use input_bindings::{Action, Binding, InputBindings, KeyPress};
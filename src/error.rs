@@ -1,8 +1,13 @@
+use std::{fmt, io, sync::Arc};
+
 #[derive(Debug, Clone)]
 pub enum WrappedError {
     GameError(ggez::GameError),
     RonError(ron::error::Error),
     SheetError(String),
+    ArgumentError(String),
+    LayoutError(String),
+    IoError(Arc<io::Error>),
 }
 
 #[derive(Debug, Clone)]
@@ -20,6 +25,12 @@ impl LocatedError {
     }
 }
 
+impl fmt::Display for LocatedError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:?} {}", self.error, self.location)
+    }
+}
+
 impl From<ggez::GameError> for WrappedError {
     fn from(error: ggez::GameError) -> Self {
         Self::GameError(error)
@@ -32,6 +43,12 @@ impl From<ron::error::Error> for WrappedError {
     }
 }
 
+impl From<io::Error> for WrappedError {
+    fn from(error: io::Error) -> Self {
+        Self::IoError(Arc::new(error))
+    }
+}
+
 #[macro_export]
 macro_rules! err_here {
     () => {
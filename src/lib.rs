@@ -1,6 +1,11 @@
+pub mod board;
 pub mod config;
 pub mod error;
+pub mod replay;
+pub mod save_game;
+pub mod screen;
 pub mod sprite_sheet;
 pub mod state;
 pub mod states;
+pub mod statistics;
 pub mod ui;
@@ -1,7 +1,14 @@
 use std::{env, path};
 
-use ggez::{conf::WindowMode, event, graphics, ContextBuilder};
-use ggsweep::{config::GameConfig, err_here, error::LocatedError, states::GameState};
+use ggez::{event, graphics, ContextBuilder};
+use ggsweep::{
+    board::{Generation, Layout},
+    config::GameConfig,
+    err_here,
+    error::{LocatedError, WrappedError},
+    screen::set_screen_size,
+    states::UiState,
+};
 use log::info;
 
 use ggsweep::states::MainState;
@@ -35,30 +42,66 @@ fn main() -> Result<(), LocatedError> {
     info!("{}", graphics::renderer_info(ctx).map_err(err_here!())?);
 
     //Load config
-    let game_config_file = ggez::filesystem::open(ctx, "/config.ron").map_err(err_here!())?;
-    let game_config: GameConfig = ron::de::from_reader(game_config_file).map_err(err_here!())?;
-
-    //Set window mode
-    let mode = WindowMode::default().dimensions(
-        game_config.game_size.0 as f32 * game_config.square_size,
-        game_config.game_size.1 as f32 * game_config.square_size,
-    );
-    graphics::set_mode(ctx, mode).map_err(err_here!())?;
-
-    //Set the screen coordinates
-    let screen_rect = graphics::Rect::new(
-        0.0,
-        0.0,
-        game_config.game_size.0 as f32 * game_config.square_size,
-        game_config.game_size.1 as f32 * game_config.square_size,
-    );
-    graphics::set_screen_coordinates(ctx, screen_rect).map_err(err_here!())?;
-
-    // Set the initial state
-    let initial_state = Box::new(GameState::new(ctx, game_config).map_err(err_here!())?);
+    let mut game_config = GameConfig::load(ctx)?;
+
+    //A seed given on the command line overrides the seed in the config
+    if let Some(seed) = parse_seed_argument()? {
+        info!("Using seed {} from the command line", seed);
+        game_config.seed = Some(seed);
+    }
+
+    // Start on the main menu
+    set_screen_size(ctx, UiState::MENU_SIZE)?;
+    let mut main_menu = UiState::create_main_menu_state(ctx, game_config.clone())?;
+
+    // A board given on the command line is played right away and the menu is shown after it
+    if let Some(path) = parse_argument("--board")? {
+        // The size limits of custom games do not apply, the window fits itself to the board
+        let layout = Layout::load(path::Path::new(&path))?;
+        info!("Playing the board from {}", path);
+
+        let mut board_config = game_config;
+        board_config.game_size = layout.size();
+        board_config.number_of_mines = layout.number_of_mines();
+        // The mines come from the layout so they are never generated
+        board_config.generation = Generation::Classic;
+
+        main_menu = main_menu.start_layout(board_config, layout);
+    }
+
+    let initial_state = Box::new(main_menu);
     let state = &mut MainState::new(initial_state, graphics::Color::from_rgb(38, 38, 38))
         .map_err(err_here!())?;
 
     // Run
     event::run(ctx, events_loop, state).map_err(err_here!())
 }
+
+/// Looks for `--seed <seed>` among the command line arguments
+fn parse_seed_argument() -> Result<Option<u64>, LocatedError> {
+    match parse_argument("--seed")? {
+        Some(seed) => seed
+            .parse()
+            .map(Some)
+            .map_err(|_| WrappedError::ArgumentError(format!("{} is not a valid seed", seed)))
+            .map_err(err_here!()),
+        None => Ok(None),
+    }
+}
+
+/// Looks for `<name> <value>` among the command line arguments
+fn parse_argument(name: &str) -> Result<Option<String>, LocatedError> {
+    let mut args = env::args().skip(1);
+
+    while let Some(arg) = args.next() {
+        if arg == name {
+            return args
+                .next()
+                .map(Some)
+                .ok_or_else(|| WrappedError::ArgumentError(format!("{} needs a value", name)))
+                .map_err(err_here!());
+        }
+    }
+
+    Ok(None)
+}
@@ -0,0 +1,194 @@
+use std::{
+    fs,
+    path::PathBuf,
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use ggez::{filesystem, Context};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, Clicks, History, Layout, Move},
+    config::GameConfig,
+    err_here,
+    error::LocatedError,
+};
+
+/// Something the player did during a game
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub enum ReplayAction {
+    Move(Move),
+    Undo,
+    Redo,
+}
+
+/// An action together with the time since the game started when it was made
+#[derive(Serialize, Deserialize, Clone, Copy, Debug, PartialEq)]
+pub struct TimedAction {
+    pub time: Duration,
+    pub action: ReplayAction,
+}
+
+/// # Replay
+/// Everything needed to play a game again: the config, the seed the mines were generated from
+/// or the layout they were placed from and every action the player made
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Replay {
+    /// The version of the replay format, replays with another version are not loaded
+    pub version: u32,
+    pub game_config: GameConfig,
+    pub seed: u64,
+    /// The mines of a board loaded from a file or played again, the seed is not used when it is set
+    #[serde(default)]
+    pub layout: Option<Layout>,
+    /// Seconds since the unix epoch when the game started, used to name the replay file
+    pub started: u64,
+    pub actions: Vec<TimedAction>,
+}
+
+impl Replay {
+    /// The current version of the replay format
+    pub const VERSION: u32 = 1;
+
+    /// The directory in the user data directory where the replays are saved
+    const DIRECTORY: &'static str = "replays";
+
+    /// Start recording a game played on a board generated from `seed`
+    pub fn new(game_config: GameConfig, seed: u64) -> Self {
+        let started = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_secs())
+            .unwrap_or_default();
+
+        Replay {
+            version: Self::VERSION,
+            game_config,
+            seed,
+            layout: None,
+            started,
+            actions: vec![],
+        }
+    }
+
+    /// Set the layout the mines of the game were placed from
+    pub fn set_layout(mut self, layout: Layout) -> Self {
+        self.layout = Some(layout);
+        self
+    }
+
+    /// Remember an action made `time` after the game started
+    pub fn record(&mut self, time: Duration, action: ReplayAction) {
+        self.actions.push(TimedAction { time, action });
+    }
+
+    /// The time of the last action
+    pub fn duration(&self) -> Duration {
+        self.actions
+            .last()
+            .map(|action| action.time)
+            .unwrap_or_default()
+    }
+
+    /// Count the clicks of every move made, moves that were undone still count
+    pub fn clicks(&self) -> Clicks {
+        let mut clicks = Clicks::default();
+        for timed in &self.actions {
+            if let ReplayAction::Move(player_move) = timed.action {
+                clicks.add(player_move);
+            }
+        }
+        clicks
+    }
+
+    /// Create the board as it was before the first action
+    pub fn new_board(&self) -> Board {
+        let board = Board::new(
+            self.game_config.game_size,
+            self.game_config.number_of_mines,
+            self.seed,
+        )
+        .set_generation(self.game_config.generation)
+        .set_win_condition(self.game_config.win_condition)
+        .set_topology(self.game_config.topology)
+        .set_question_marks(self.game_config.question_marks);
+
+        match &self.layout {
+            Some(layout) => board.set_layout(layout),
+            None => board,
+        }
+    }
+
+    /// Make an action on the board the same way it was made in the game
+    pub fn apply(&self, action: ReplayAction, board: &mut Board, history: &mut History) {
+        match action {
+            ReplayAction::Move(player_move) => history.apply(board, player_move),
+            ReplayAction::Undo => {
+                history.undo(board, self.game_config.practice);
+            }
+            ReplayAction::Redo => {
+                history.redo(board);
+            }
+        }
+    }
+
+    fn directory(ctx: &Context) -> PathBuf {
+        filesystem::user_data_dir(ctx).join(Self::DIRECTORY)
+    }
+
+    /// Write the replay to the replays directory.
+    /// Writing the same game again replaces the earlier file
+    pub fn write(&self, ctx: &Context) -> Result<(), LocatedError> {
+        let directory = Self::directory(ctx);
+        fs::create_dir_all(&directory).map_err(err_here!())?;
+
+        let path = directory.join(format!("replay-{}-{}.ron", self.started, self.seed));
+        let text =
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()).map_err(err_here!())?;
+        fs::write(&path, text).map_err(err_here!())?;
+
+        info!("Saved replay to {:?}", path);
+        Ok(())
+    }
+
+    /// The path of the most recently saved replay
+    fn latest_path(ctx: &Context) -> Option<PathBuf> {
+        fs::read_dir(Self::directory(ctx))
+            .ok()?
+            .flatten()
+            .map(|entry| entry.path())
+            .filter(|path| path.extension().is_some_and(|e| e == "ron"))
+            .max_by_key(|path| fs::metadata(path).and_then(|m| m.modified()).ok())
+    }
+
+    /// Returns true if a replay has been saved
+    pub fn exists(ctx: &Context) -> bool {
+        Self::latest_path(ctx).is_some()
+    }
+
+    /// Load the most recently saved replay.
+    /// Replays that can not be read or have another version are ignored
+    pub fn load_latest(ctx: &Context) -> Result<Option<Self>, LocatedError> {
+        let path = match Self::latest_path(ctx) {
+            Some(path) => path,
+            None => return Ok(None),
+        };
+
+        let text = fs::read_to_string(&path).map_err(err_here!())?;
+        match ron::de::from_str::<Replay>(&text) {
+            Ok(replay) if replay.version == Self::VERSION => Ok(Some(replay)),
+            Ok(replay) => {
+                warn!(
+                    "Ignoring replay with version {}, expected {}",
+                    replay.version,
+                    Self::VERSION
+                );
+                Ok(None)
+            }
+            Err(e) => {
+                warn!("Ignoring replay that could not be read: {:?}", e);
+                Ok(None)
+            }
+        }
+    }
+}
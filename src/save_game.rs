@@ -0,0 +1,133 @@
+use std::{fs, path::PathBuf, time::Duration};
+
+use ggez::{filesystem, Context};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Board, History},
+    config::GameConfig,
+    err_here,
+    error::LocatedError,
+    replay::Replay,
+};
+
+/// # Save Game
+/// A game in progress saved to the user data directory when the game is closed
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SaveGame {
+    /// The version of the save format, saves with another version are not loaded
+    pub version: u32,
+    pub game_config: GameConfig,
+    pub board: Board,
+    pub history: History,
+    pub replay: Replay,
+    pub elapsed: Duration,
+    /// The amount of hints shown so far
+    #[serde(default)]
+    pub hints: u32,
+    /// Wether the analysis has been shown
+    #[serde(default)]
+    pub used_analysis: bool,
+    /// Wether the layout of the replay was loaded from a file and not a board played again
+    #[serde(default)]
+    pub loaded: bool,
+}
+
+impl SaveGame {
+    /// The current version of the save format
+    pub const VERSION: u32 = 5;
+
+    /// The name of the save file in the user data directory
+    const FILE_NAME: &'static str = "save.ron";
+
+    pub fn new(
+        game_config: GameConfig,
+        board: Board,
+        history: History,
+        replay: Replay,
+        elapsed: Duration,
+    ) -> Self {
+        SaveGame {
+            version: Self::VERSION,
+            game_config,
+            board,
+            history,
+            replay,
+            elapsed,
+            hints: 0,
+            used_analysis: false,
+            loaded: false,
+        }
+    }
+
+    /// Set the amount of hints shown so far
+    pub fn set_hints(mut self, hints: u32) -> Self {
+        self.hints = hints;
+        self
+    }
+
+    /// Set wether the analysis has been shown
+    pub fn set_used_analysis(mut self, used_analysis: bool) -> Self {
+        self.used_analysis = used_analysis;
+        self
+    }
+
+    /// Set wether the layout of the replay was loaded from a file
+    pub fn set_loaded(mut self, loaded: bool) -> Self {
+        self.loaded = loaded;
+        self
+    }
+
+    fn path(ctx: &Context) -> PathBuf {
+        filesystem::user_data_dir(ctx).join(Self::FILE_NAME)
+    }
+
+    /// Returns true if there is a saved game
+    pub fn exists(ctx: &Context) -> bool {
+        Self::path(ctx).is_file()
+    }
+
+    /// Write the game to the save file, replacing any earlier save
+    pub fn write(&self, ctx: &Context) -> Result<(), LocatedError> {
+        let path = Self::path(ctx);
+        fs::create_dir_all(filesystem::user_data_dir(ctx)).map_err(err_here!())?;
+
+        let text =
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()).map_err(err_here!())?;
+        fs::write(&path, text).map_err(err_here!())?;
+
+        info!("Saved game to {:?}", path);
+        Ok(())
+    }
+
+    /// Load the saved game and remove the save file so the same game can not be continued twice.
+    /// Saves that can not be read or have another version are ignored and left where they are
+    pub fn take(ctx: &Context) -> Result<Option<Self>, LocatedError> {
+        if !Self::exists(ctx) {
+            return Ok(None);
+        }
+
+        let path = Self::path(ctx);
+        let text = fs::read_to_string(&path).map_err(err_here!())?;
+
+        match ron::de::from_str::<SaveGame>(&text) {
+            Ok(save) if save.version == Self::VERSION => {
+                fs::remove_file(&path).map_err(err_here!())?;
+                Ok(Some(save))
+            }
+            Ok(save) => {
+                warn!(
+                    "Ignoring save with version {}, expected {}",
+                    save.version,
+                    Self::VERSION
+                );
+                Ok(None)
+            }
+            Err(e) => {
+                warn!("Ignoring save that could not be read: {:?}", e);
+                Ok(None)
+            }
+        }
+    }
+}
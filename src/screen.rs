@@ -0,0 +1,14 @@
+use ggez::{conf::WindowMode, graphics, Context};
+
+use crate::{err_here, error::LocatedError};
+
+/// Resize the window and set the screen coordinates to match the new size
+pub fn set_screen_size(ctx: &mut Context, (width, height): (f32, f32)) -> Result<(), LocatedError> {
+    //Set window mode
+    let mode = WindowMode::default().dimensions(width, height);
+    graphics::set_mode(ctx, mode).map_err(err_here!())?;
+
+    //Set the screen coordinates
+    let screen_rect = graphics::Rect::new(0.0, 0.0, width, height);
+    graphics::set_screen_coordinates(ctx, screen_rect).map_err(err_here!())
+}
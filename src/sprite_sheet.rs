@@ -60,6 +60,13 @@ pub struct SpriteSheet {
 }
 
 impl SpriteSheet {
+    /// Load the sheet info from a ron file and create a `SpriteSheet` using the image file called `file_name`
+    pub fn load(ctx: &mut Context, info_path: &str, file_name: &str) -> Result<Self, LocatedError> {
+        let file = filesystem::open(ctx, info_path).map_err(err_here!())?;
+        let sheet_info: SheetInfo = from_reader(file).map_err(err_here!())?;
+        sheet_info.into_sprite_sheet(ctx, file_name)
+    }
+
     /// Create an new `SpriteSheet`    
     fn new(
         ctx: &mut Context,
@@ -99,6 +106,11 @@ impl SpriteSheet {
         })
     }
 
+    /// The name of the sheet as written in the sheet info
+    pub fn name(&self) -> &str {
+        &self.sheet_info.name
+    }
+
     pub fn get_sprite_and_rect(
         &self,
         sprite_name: &str,
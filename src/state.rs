@@ -9,6 +9,19 @@ pub enum EventResult {
     Block,
 }
 
+/// What a state that pops itself asks the state below it to do next
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum Message {
+    /// Start a new game with the same settings
+    PlayAgain,
+    /// Play the board with the same mines again
+    ReplayBoard,
+    /// Go back to the main menu to pick another difficulty
+    ChangeDifficulty,
+    /// Close the game
+    Quit,
+}
+
 ///Enum returned from the update function can also tell the state stack to push or swap a state
 #[allow(dead_code)]
 pub enum UpdateResult {
@@ -22,6 +35,8 @@ pub enum UpdateResult {
     Push(Box<dyn State>),
     /// Pop the topmost state
     Pop,
+    /// Pop the topmost state and send the message to the state below it
+    PopWith(Message),
 }
 
 /// A state in a state stack   
@@ -73,6 +88,31 @@ pub trait State {
         Ok(EventResult::LetThrough)
     }
 
+    /// Called with the message from the state above this one when it pops itself
+    fn message_event(
+        &mut self,
+        _ctx: &mut ggez::Context,
+        _message: Message,
+    ) -> Result<(), LocatedError> {
+        Ok(())
+    }
+
+    /// Called when the game is about to close
+    fn quit_event(&mut self, _ctx: &mut ggez::Context) -> Result<(), LocatedError> {
+        Ok(())
+    }
+
+    /// Called when a key is pressed, `repeat` is set for the presses repeated while the key is held
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut ggez::Context,
+        _keycode: ggez::input::keyboard::KeyCode,
+        _keymods: ggez::input::keyboard::KeyMods,
+        _repeat: bool,
+    ) -> ggez::GameResult<EventResult> {
+        Ok(EventResult::LetThrough)
+    }
+
     fn key_up_event(
         &mut self,
         _ctx: &mut ggez::Context,
@@ -81,4 +121,13 @@ pub trait State {
     ) -> ggez::GameResult<EventResult> {
         Ok(EventResult::LetThrough)
     }
+
+    /// Called when a character is typed
+    fn text_input_event(
+        &mut self,
+        _ctx: &mut ggez::Context,
+        _character: char,
+    ) -> ggez::GameResult<EventResult> {
+        Ok(EventResult::LetThrough)
+    }
 }
@@ -0,0 +1,213 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+
+use ggez::{graphics, Context, GameResult};
+use log::trace;
+
+use crate::board::{
+    probability::{self, ProbabilityError},
+    Board, SquareState,
+};
+
+use super::board_view::BoardView;
+
+/// The chance of a mine for every square or why it could not be worked out
+type Probabilities = Result<Vec<Option<f64>>, ProbabilityError>;
+
+/// Probabilities being worked out on another thread
+struct Worker {
+    /// The squares of the board the probabilities are worked out for
+    squares: Vec<SquareState>,
+    result: mpsc::Receiver<Probabilities>,
+    cancel: Arc<AtomicBool>,
+}
+
+/// # Analysis
+/// The chance of a mine under every closed square drawn as a heatmap over the board,
+/// green for safe squares and red for mines. Flags are taken to be mines.
+/// The chances are worked out on another thread every time the board changes so the game
+/// keeps running while a large board is analysed
+pub struct Analysis {
+    font: graphics::Font,
+    worker: Option<Worker>,
+    /// The squares of the board the heatmap was made for
+    squares: Vec<SquareState>,
+    heatmap: Option<graphics::Mesh>,
+    /// The chance in percent of every closed square and where it is drawn
+    labels: Vec<(String, cgmath::Point2<f32>)>,
+    /// Why the chances of the last board could not be worked out
+    error: Option<ProbabilityError>,
+}
+
+impl Analysis {
+    /// The font size of the chances
+    const LABEL_SIZE: f32 = 10.0;
+
+    pub fn new(font: graphics::Font) -> Self {
+        Analysis {
+            font,
+            worker: None,
+            squares: vec![],
+            heatmap: None,
+            labels: vec![],
+            error: None,
+        }
+    }
+
+    /// Pick up finished probabilities and start working them out again if the board has changed
+    pub fn update(&mut self, ctx: &mut Context, board: &Board, view: &BoardView) -> GameResult {
+        let finished = self
+            .worker
+            .as_ref()
+            .and_then(|worker| worker.result.try_recv().ok());
+        if let Some(probabilities) = finished {
+            if let Some(worker) = self.worker.take() {
+                self.squares = worker.squares;
+            }
+            self.build(ctx, board, view, probabilities)?;
+        }
+
+        let working_on = self.worker.as_ref().map(|worker| worker.squares.as_slice());
+        if self.squares == board.squares() || working_on == Some(board.squares()) {
+            return Ok(());
+        }
+
+        // The board changed so the probabilities being worked out are outdated
+        self.cancel();
+        self.worker = Some(Self::start(board));
+        Ok(())
+    }
+
+    /// Start working out the probabilities of the board on another thread
+    fn start(board: &Board) -> Worker {
+        let (sender, result) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let squares = board.squares().to_vec();
+        let board = board.clone();
+        let thread_cancel = cancel.clone();
+        thread::spawn(move || {
+            let known_mines = board.get_flagged_squares();
+            let probabilities =
+                probability::cancellable_mine_probabilities(&board, &known_mines, &thread_cancel);
+
+            if thread_cancel.load(Ordering::Relaxed) {
+                trace!("Stopped working out outdated probabilities");
+            } else {
+                // The analysis may have been closed in the meantime
+                let _ = sender.send(probabilities);
+            }
+        });
+
+        Worker {
+            squares,
+            result,
+            cancel,
+        }
+    }
+
+    /// Stop the calculation that is running
+    fn cancel(&mut self) {
+        if let Some(worker) = self.worker.take() {
+            worker.cancel.store(true, Ordering::Relaxed);
+        }
+    }
+
+    /// Make the heatmap and the labels from the probabilities
+    fn build(
+        &mut self,
+        ctx: &mut Context,
+        board: &Board,
+        view: &BoardView,
+        probabilities: Probabilities,
+    ) -> GameResult {
+        self.heatmap = None;
+        self.labels.clear();
+        self.error = probabilities.as_ref().err().copied();
+
+        let closed: Vec<(usize, f64)> = probabilities
+            .unwrap_or_default()
+            .into_iter()
+            .enumerate()
+            .filter_map(|(index, probability)| probability.map(|p| (index, p)))
+            .collect();
+
+        if closed.is_empty() {
+            return Ok(());
+        }
+
+        let mut builder = graphics::MeshBuilder::new();
+        for &(index, probability) in &closed {
+            let rect = view.square_rect(board, index);
+            let p = probability as f32;
+            builder.polygon(
+                graphics::DrawMode::fill(),
+                &view.square_corners(board, index),
+                graphics::Color::new(p, 1.0 - p, 0.0, 0.45),
+            )?;
+
+            self.labels.push((
+                format!("{:.0}", probability * 100.0),
+                cgmath::point2(rect.x + 2.0, rect.y + rect.h - Self::LABEL_SIZE - 2.0),
+            ));
+        }
+        self.heatmap = Some(builder.build(ctx)?);
+
+        Ok(())
+    }
+
+    /// Draw the heatmap if it is up to date with the board, otherwise a line saying why it is missing
+    pub fn draw(
+        &self,
+        ctx: &mut Context,
+        board: &Board,
+        position: cgmath::Point2<f32>,
+    ) -> GameResult {
+        let status = if self.squares != board.squares() {
+            Some("Working out the chances")
+        } else {
+            match self.error {
+                Some(ProbabilityError::NoPlacement) => Some("The flags do not fit the numbers"),
+                Some(ProbabilityError::TooManyCombinations) => {
+                    Some("Too many ways to place the mines to work out the chances")
+                }
+                // Cancelled chances are never sent to the analysis
+                Some(ProbabilityError::Cancelled) | None => None,
+            }
+        };
+
+        if let Some(status) = status {
+            let mut text = graphics::Text::new(status);
+            text.set_font(self.font, graphics::Scale::uniform(12.0));
+            return graphics::draw(ctx, &text, graphics::DrawParam::default().dest(position));
+        }
+
+        if let Some(heatmap) = &self.heatmap {
+            graphics::draw(ctx, heatmap, graphics::DrawParam::default())?;
+        }
+
+        for (label, position) in &self.labels {
+            let mut text = graphics::Text::new(label.as_str());
+            text.set_font(self.font, graphics::Scale::uniform(Self::LABEL_SIZE));
+            graphics::queue_text(ctx, &text, *position, Some(graphics::WHITE));
+        }
+        graphics::draw_queued_text(
+            ctx,
+            graphics::DrawParam::default(),
+            None,
+            graphics::FilterMode::Linear,
+        )
+    }
+}
+
+impl Drop for Analysis {
+    /// A closed analysis does not need its probabilities anymore
+    fn drop(&mut self) {
+        self.cancel();
+    }
+}
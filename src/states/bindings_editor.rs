@@ -0,0 +1,118 @@
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics, Context, GameResult,
+};
+
+use crate::{
+    config::{Action, GameConfig, KeyPress},
+    error::LocatedError,
+};
+
+/// # Bindings Editor
+/// The bindings being changed in the controls screen.
+/// After an action is picked the next key or mouse button pressed is bound to it and delete clears it
+pub struct BindingsEditor {
+    game_config: GameConfig,
+    /// The action waiting for a key or mouse button
+    waiting: Option<Action>,
+    font: graphics::Font,
+}
+
+impl BindingsEditor {
+    /// The x position of the key and mouse button names
+    const BINDING_X: f32 = 120.0;
+
+    /// Edit the bindings of the config, the rest of the config is written back as it is
+    pub fn new(game_config: GameConfig, font: graphics::Font) -> Self {
+        BindingsEditor {
+            game_config,
+            waiting: None,
+            font,
+        }
+    }
+
+    /// The y position of the row for the action in the controls screen
+    pub fn row_y(action: Action) -> f32 {
+        let row = Action::ALL.iter().position(|a| *a == action).unwrap_or(0);
+        80.0 + row as f32 * 50.0
+    }
+
+    /// Wait for the next key or mouse button to bind to the action
+    pub fn start_rebinding(&mut self, action: Action) {
+        self.waiting = Some(action);
+    }
+
+    pub fn is_waiting(&self) -> bool {
+        self.waiting.is_some()
+    }
+
+    /// Bind the key to the waiting action, delete clears the binding of the action instead
+    pub fn bind_key(&mut self, key: KeyCode, keymods: KeyMods) {
+        if let Some(action) = self.waiting.take() {
+            let bindings = &mut self.game_config.bindings;
+            if key == KeyCode::Delete {
+                let binding = bindings.binding_mut(action);
+                binding.key = None;
+                binding.mouse = None;
+            } else if keymods.contains(KeyMods::CTRL) {
+                bindings.bind_key(action, KeyPress::with_ctrl(key));
+            } else {
+                bindings.bind_key(action, KeyPress::new(key));
+            }
+        }
+    }
+
+    /// Bind the mouse button to the waiting action
+    pub fn bind_mouse(&mut self, button: MouseButton) {
+        if let Some(action) = self.waiting.take() {
+            self.game_config.bindings.bind_mouse(action, button);
+        }
+    }
+
+    /// Write the config with the new bindings to the user config directory
+    pub fn save(&self, ctx: &mut Context) -> Result<(), LocatedError> {
+        self.game_config.write(ctx)
+    }
+
+    /// Draw the name, key and mouse button of every action
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        for action in Action::ALL.iter() {
+            let y = Self::row_y(*action);
+            let binding = self.game_config.bindings.binding(*action);
+
+            let lines = if self.waiting == Some(*action) {
+                vec!["Press a key".to_string(), "or button".to_string()]
+            } else {
+                vec![
+                    format!(
+                        "Key: {}",
+                        binding.key.map_or("-".to_string(), |press| press.name())
+                    ),
+                    format!(
+                        "Mouse: {}",
+                        binding
+                            .mouse
+                            .map_or("-".to_string(), |button| format!("{:?}", button))
+                    ),
+                ]
+            };
+
+            let mut name = graphics::Text::new(action.name());
+            name.set_font(self.font, graphics::Scale::uniform(16.0));
+            graphics::draw(
+                ctx,
+                &name,
+                graphics::DrawParam::default().dest(cgmath::point2(32.0, y + 16.0)),
+            )?;
+
+            for (i, line) in lines.into_iter().enumerate() {
+                let mut text = graphics::Text::new(line);
+                text.set_font(self.font, graphics::Scale::uniform(12.0));
+                let position = cgmath::point2(Self::BINDING_X, y + 8.0 + i as f32 * 18.0);
+                graphics::draw(ctx, &text, graphics::DrawParam::default().dest(position))?;
+            }
+        }
+
+        Ok(())
+    }
+}
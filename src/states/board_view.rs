@@ -0,0 +1,363 @@
+use cgmath::{EuclideanSpace, InnerSpace};
+use ggez::{graphics, Context, GameResult};
+
+use crate::{
+    board::{Board, IndexType, Mark, SquareState, Status, Topology},
+    config::{GameColors, GameConfig},
+};
+
+/// Used for points
+type Point2 = cgmath::Point2<f32>;
+
+const SQRT_3: f32 = 1.732_050_8;
+
+/// # Board View
+/// Draws the squares of a board below the hud and finds the square under a position on the screen.
+/// Hexagons are as wide as a square and triangles are as high as a square
+pub struct BoardView {
+    flag_image: graphics::Image,
+    question_image: graphics::Image,
+    number_image: graphics::Image,
+    mine_image: graphics::Image,
+    /// The shape of a square drawn from the top left corner of the box around it,
+    /// triangles pointing down use the second shape
+    shapes: Vec<graphics::Mesh>,
+    /// Drawn over flags on squares without a mine when the game is lost
+    cross: graphics::Mesh,
+    /// Used for the numbers above 8 that only triangles can have
+    font: graphics::Font,
+    square_size: f32,
+    topology: Topology,
+    colors: GameColors,
+    /// The position of the top left corner of the board on the screen
+    offset: cgmath::Vector2<f32>,
+}
+
+impl BoardView {
+    /// Create a view drawing the board with its top left corner at `offset`
+    pub fn new(
+        ctx: &mut Context,
+        game_config: &GameConfig,
+        offset: cgmath::Vector2<f32>,
+    ) -> GameResult<Self> {
+        let flag_image = graphics::Image::new(ctx, "\\flag.png")?;
+        let question_image = graphics::Image::new(ctx, "\\question.png")?;
+        let number_image = graphics::Image::new(ctx, "\\spr_numbers.png")?;
+        let mine_image = graphics::Image::new(ctx, "\\mine.png")?;
+        let font = graphics::Font::new(ctx, "/Kenney_Future.ttf")?;
+
+        let topology = game_config.topology;
+        let square_size = game_config.square_size;
+
+        // Hexagons and triangles are drawn a little smaller so the shape of every square shows
+        let orientations: &[bool] = match topology {
+            Topology::Triangle => &[true, false],
+            Topology::Square | Topology::Hex => &[true],
+        };
+        let shapes = orientations
+            .iter()
+            .map(|&points_up| {
+                let outline = Self::outline(topology, square_size, points_up);
+                let outline = match topology {
+                    Topology::Square => outline,
+                    Topology::Hex | Topology::Triangle => Self::shrink(&outline, 1.0),
+                };
+                graphics::Mesh::new_polygon(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    &outline,
+                    graphics::WHITE,
+                )
+            })
+            .collect::<GameResult<_>>()?;
+
+        let size = Self::image_size(topology, square_size);
+        let margin = size / 8.0;
+        let cross_color = game_config.colors.mine_square.into();
+        let cross = graphics::MeshBuilder::new()
+            .line(
+                &[[margin, margin], [size - margin, size - margin]],
+                size / 10.0,
+                cross_color,
+            )?
+            .line(
+                &[[size - margin, margin], [margin, size - margin]],
+                size / 10.0,
+                cross_color,
+            )?
+            .build(ctx)?;
+
+        Ok(BoardView {
+            flag_image,
+            question_image,
+            number_image,
+            mine_image,
+            shapes,
+            cross,
+            font,
+            square_size,
+            topology,
+            colors: game_config.colors.clone(),
+            offset,
+        })
+    }
+
+    /// The width and height of the board on the screen
+    pub fn board_size(game_config: &GameConfig) -> (f32, f32) {
+        let topology = game_config.topology;
+        let size = game_config.square_size;
+        let (width, height) = game_config.game_size;
+        let (column, row) = Self::spacing(topology, size);
+        let (square_width, square_height) = Self::bounds(topology, size);
+
+        // Every odd row of hexagons sticks out half a hexagon
+        let shifted = if topology == Topology::Hex && height > 1 {
+            size / 2.0
+        } else {
+            0.0
+        };
+
+        (
+            (width as f32 - 1.0) * column + square_width + shifted,
+            (height as f32 - 1.0) * row + square_height,
+        )
+    }
+
+    /// The distance between the columns and between the rows
+    fn spacing(topology: Topology, size: f32) -> (f32, f32) {
+        match topology {
+            Topology::Square => (size, size),
+            Topology::Hex => (size, size * SQRT_3 / 2.0),
+            Topology::Triangle => (size / SQRT_3, size),
+        }
+    }
+
+    /// The width and height of the box around a square
+    fn bounds(topology: Topology, size: f32) -> (f32, f32) {
+        match topology {
+            Topology::Square => (size, size),
+            Topology::Hex => (size, size * 2.0 / SQRT_3),
+            Topology::Triangle => (size * 2.0 / SQRT_3, size),
+        }
+    }
+
+    /// The corners of a square relative to the top left corner of the box around it
+    fn outline(topology: Topology, size: f32, points_up: bool) -> Vec<Point2> {
+        let (w, h) = Self::bounds(topology, size);
+        let corners: &[[f32; 2]] = match topology {
+            Topology::Square => &[[0.0, 0.0], [w, 0.0], [w, h], [0.0, h]],
+            Topology::Hex => &[
+                [w / 2.0, 0.0],
+                [w, h / 4.0],
+                [w, h * 3.0 / 4.0],
+                [w / 2.0, h],
+                [0.0, h * 3.0 / 4.0],
+                [0.0, h / 4.0],
+            ],
+            Topology::Triangle if points_up => &[[w / 2.0, 0.0], [w, h], [0.0, h]],
+            Topology::Triangle => &[[0.0, 0.0], [w, 0.0], [w / 2.0, h]],
+        };
+        corners.iter().map(|&[x, y]| Point2::new(x, y)).collect()
+    }
+
+    /// Move the corners of a shape `amount` closer to its middle
+    fn shrink(corners: &[Point2], amount: f32) -> Vec<Point2> {
+        let count = corners.len() as f32;
+        let middle = Point2::new(
+            corners.iter().map(|c| c.x).sum::<f32>() / count,
+            corners.iter().map(|c| c.y).sum::<f32>() / count,
+        );
+        corners
+            .iter()
+            .map(|&corner| corner + (middle - corner).normalize_to(amount))
+            .collect()
+    }
+
+    /// The width and height of the images drawn on a square, the largest that fits the shape
+    fn image_size(topology: Topology, size: f32) -> f32 {
+        match topology {
+            Topology::Square => size,
+            Topology::Hex => size * 0.75,
+            Topology::Triangle => size * 0.6,
+        }
+    }
+
+    /// The top left corner of the box around the square at the point on the screen
+    fn origin(&self, point: cgmath::Vector2<i32>) -> Point2 {
+        let (column, row) = Self::spacing(self.topology, self.square_size);
+        let shift = if self.topology == Topology::Hex && point.y % 2 == 1 {
+            self.square_size / 2.0
+        } else {
+            0.0
+        };
+
+        Point2::new(point.x as f32 * column + shift, point.y as f32 * row) + self.offset
+    }
+
+    /// The corners of the square on the screen
+    pub fn square_corners(&self, board: &Board, index: IndexType) -> Vec<Point2> {
+        let point = board.index_to_point(index);
+        let origin = self.origin(point).to_vec();
+        Self::outline(self.topology, self.square_size, Topology::points_up(point))
+            .into_iter()
+            .map(|corner| corner + origin)
+            .collect()
+    }
+
+    /// The square under the position on the screen if there is one.
+    /// Hexagons and triangles reach into the boxes of their neighbors so the squares around are tried as well
+    pub fn index_at(&self, board: &Board, x: f32, y: f32) -> Option<IndexType> {
+        let (column, row) = Self::spacing(self.topology, self.square_size);
+        let guess = cgmath::Vector2::<i32>::new(
+            ((x - self.offset.x) / column).floor() as i32,
+            ((y - self.offset.y) / row).floor() as i32,
+        );
+
+        let position = Point2::new(x, y);
+        let near = (-1..=1).flat_map(|dy| (-2..=1).map(move |dx| cgmath::vec2(dx, dy)));
+        std::iter::once(cgmath::vec2(0, 0))
+            .chain(near)
+            .map(|offset| guess + offset)
+            .filter(|point| board.contains_point(*point))
+            .map(|point| board.point_to_index(point))
+            .find(|index| Self::contains(&self.square_corners(board, *index), position))
+    }
+
+    /// Returns true if the position is inside the shape with the corners going around it
+    fn contains(corners: &[Point2], position: Point2) -> bool {
+        let sides = corners.iter().zip(corners.iter().cycle().skip(1));
+        let turns: Vec<f32> = sides
+            .map(|(a, b)| (b.x - a.x) * (position.y - a.y) - (b.y - a.y) * (position.x - a.x))
+            .collect();
+        turns.iter().all(|turn| *turn >= 0.0) || turns.iter().all(|turn| *turn <= 0.0)
+    }
+
+    /// The area of the square on the screen where its image is drawn, the whole square for squares
+    pub fn square_rect(&self, board: &Board, index: IndexType) -> graphics::Rect {
+        let point = board.index_to_point(index);
+        let (width, height) = Self::bounds(self.topology, self.square_size);
+        let size = Self::image_size(self.topology, self.square_size);
+
+        // The middle of a triangle is a third of the way from its flat side
+        let middle_y = match self.topology {
+            Topology::Triangle if Topology::points_up(point) => height * 2.0 / 3.0,
+            Topology::Triangle => height / 3.0,
+            Topology::Square | Topology::Hex => height / 2.0,
+        };
+
+        let origin = self.origin(point);
+        graphics::Rect::new(
+            origin.x + (width - size) / 2.0,
+            origin.y + middle_y - size / 2.0,
+            size,
+            size,
+        )
+    }
+
+    /// Draw the squares, the `selected` squares are drawn in the selected color.
+    /// A lost board shows every mine, crosses out the flags on squares without a mine
+    /// and draws the mine that lost the game on the mine color
+    pub fn draw(&self, ctx: &mut Context, board: &Board, selected: &[IndexType]) -> GameResult {
+        let lost = board.status() == Status::Lost;
+
+        for (i, square_state) in board.squares().iter().enumerate() {
+            let point = board.index_to_point(i);
+            let shape = if Topology::points_up(point) {
+                &self.shapes[0]
+            } else {
+                self.shapes.last().unwrap_or(&self.shapes[0])
+            };
+            let image_rect = self.square_rect(board, i);
+
+            let mut params = graphics::DrawParam::new();
+            params.dest = self.origin(point).into();
+
+            match *square_state {
+                SquareState::Closed(mark) => {
+                    params.color = if selected.contains(&i) {
+                        self.colors.selected_square.into()
+                    } else {
+                        self.colors.square.into()
+                    };
+
+                    graphics::draw(ctx, shape, params)?;
+
+                    let is_mine = board.is_mine(i);
+                    let image = match mark {
+                        _ if lost && is_mine && mark != Mark::Flag => Some(&self.mine_image),
+                        Mark::None => None,
+                        Mark::Flag => Some(&self.flag_image),
+                        Mark::Question => Some(&self.question_image),
+                    };
+
+                    if let Some(image) = image {
+                        self.draw_image(ctx, image, image_rect)?;
+                    }
+
+                    if lost && mark == Mark::Flag && !is_mine {
+                        graphics::draw(
+                            ctx,
+                            &self.cross,
+                            graphics::DrawParam::default().dest(image_rect.point()),
+                        )?;
+                    }
+                }
+                SquareState::Open(mine_count) => {
+                    if board.is_mine(i) {
+                        if board.detonated() == Some(i) {
+                            params.color = self.colors.mine_square.into();
+                            graphics::draw(ctx, shape, params)?;
+                        }
+
+                        self.draw_image(ctx, &self.mine_image, image_rect)?;
+                    } else if mine_count > 0 {
+                        self.draw_number(ctx, mine_count, image_rect)?;
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Draw the number of neighboring mines from the sprite sheet of the numbers 1 to 8.
+    /// The numbers above 8 are written with the font
+    fn draw_number(&self, ctx: &mut Context, mine_count: u8, rect: graphics::Rect) -> GameResult {
+        if mine_count > 8 {
+            let mut text = graphics::Text::new(mine_count.to_string());
+            text.set_font(self.font, graphics::Scale::uniform(rect.h * 0.8));
+            let position = Point2::new(
+                rect.x + (rect.w - text.width(ctx) as f32) / 2.0,
+                rect.y + (rect.h - text.height(ctx) as f32) / 2.0,
+            );
+            return graphics::draw(ctx, &text, graphics::DrawParam::default().dest(position));
+        }
+
+        let origin = cgmath::vec2((mine_count % 3) as f32, (mine_count / 3) as f32) / 3.0;
+        let scale = rect.w * 3.0 / self.number_image.dimensions().w;
+        let params = graphics::DrawParam::default()
+            .src(graphics::Rect::new(
+                origin.x,
+                origin.y,
+                1.0 / 3.0,
+                1.0 / 3.0,
+            ))
+            .dest(rect.point())
+            .scale(cgmath::vec2(scale, scale));
+        graphics::draw(ctx, &self.number_image, params)
+    }
+
+    /// Draw an image scaled to fill the rect
+    fn draw_image(
+        &self,
+        ctx: &mut Context,
+        image: &graphics::Image,
+        rect: graphics::Rect,
+    ) -> GameResult {
+        let scale = rect.w / image.dimensions().w;
+        let params = graphics::DrawParam::default()
+            .dest(rect.point())
+            .scale(cgmath::vec2(scale, scale));
+        graphics::draw(ctx, image, params)
+    }
+}
@@ -0,0 +1,110 @@
+use ggez::{graphics, Context, GameResult};
+
+use crate::config::GameConfig;
+
+/// A value that can be changed in the custom game dialog
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum CustomField {
+    Width,
+    Height,
+    Mines,
+}
+
+impl CustomField {
+    /// All the fields in the order they are shown
+    pub const ALL: [CustomField; 3] = [CustomField::Width, CustomField::Height, CustomField::Mines];
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            CustomField::Width => "Width",
+            CustomField::Height => "Height",
+            CustomField::Mines => "Mines",
+        }
+    }
+
+    /// The y position of the row for the field in the dialog
+    pub fn row_y(&self) -> f32 {
+        let row = CustomField::ALL.iter().position(|f| f == self).unwrap_or(0);
+        80.0 + row as f32 * 64.0
+    }
+}
+
+/// # Custom Game
+/// The config being edited in the custom game dialog
+pub struct CustomGame {
+    game_config: GameConfig,
+    font: graphics::Font,
+}
+
+impl CustomGame {
+    /// The horizontal center of the values between the minus and plus buttons
+    pub const VALUE_CENTER_X: f32 = 208.0;
+    /// The y position of the error message
+    pub const ERROR_Y: f32 = 272.0;
+
+    pub fn new(game_config: GameConfig, font: graphics::Font) -> Self {
+        CustomGame { game_config, font }
+    }
+
+    pub fn game_config(&self) -> &GameConfig {
+        &self.game_config
+    }
+
+    pub fn value(&self, field: CustomField) -> usize {
+        match field {
+            CustomField::Width => self.game_config.game_size.0,
+            CustomField::Height => self.game_config.game_size.1,
+            CustomField::Mines => self.game_config.number_of_mines,
+        }
+    }
+
+    /// Change a value by `amount`.
+    /// The width and height stop at the sizes allowed while the mines are checked by `validate`
+    pub fn adjust(&mut self, field: CustomField, amount: i64) {
+        let value = (self.value(field) as i64 + amount).max(0) as usize;
+
+        match field {
+            CustomField::Width => {
+                self.game_config.game_size.0 =
+                    value.clamp(GameConfig::MIN_SIZE, GameConfig::MAX_WIDTH)
+            }
+            CustomField::Height => {
+                self.game_config.game_size.1 =
+                    value.clamp(GameConfig::MIN_SIZE, GameConfig::MAX_HEIGHT)
+            }
+            CustomField::Mines => self.game_config.number_of_mines = value,
+        }
+    }
+
+    /// Check if the config makes a playable game
+    pub fn validate(&self) -> Result<(), String> {
+        self.game_config.validate()
+    }
+
+    /// Draw the values and the error message if the config is not valid
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        for field in CustomField::ALL.iter() {
+            let mut text = graphics::Text::new(self.value(*field).to_string());
+            text.set_font(self.font, graphics::Scale::uniform(24.0));
+
+            let position = cgmath::point2(
+                Self::VALUE_CENTER_X - text.width(ctx) as f32 / 2.0,
+                field.row_y() + 12.0,
+            );
+            graphics::draw(ctx, &text, graphics::DrawParam::default().dest(position))?;
+        }
+
+        if let Err(message) = self.validate() {
+            let mut text = graphics::Text::new(message);
+            text.set_font(self.font, graphics::Scale::uniform(16.0));
+
+            let position = cgmath::point2(32.0, Self::ERROR_Y);
+            let params = graphics::DrawParam::default()
+                .dest(position)
+                .color(graphics::Color::from_rgb(255, 50, 50));
+            graphics::draw(ctx, &text, params)?;
+        }
+
+        Ok(())
+    }
+}
@@ -1,330 +1,660 @@
-use std::{collections::{self, HashSet}, usize};
-
-use ggez::{graphics, Context, GameResult};
+use std::{fs, time::Duration};
+
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    filesystem, graphics, Context, GameResult,
+};
+
+use log::{error, info, trace};
+
+use crate::{
+    board::{Board, Hint, History, IndexType, Layout, Metrics, Move, SquareState, Status},
+    config::{Action, GameConfig, InputBindings},
+    err_here,
+    error::LocatedError,
+    replay::{Replay, ReplayAction},
+    save_game::SaveGame,
+    screen::set_screen_size,
+    state::*,
+    statistics::{BoardKey, GameRecord, Statistics},
+};
+
+use super::{
+    analysis::Analysis, board_view::BoardView, hint_search::HintSearch, hud::Hud, ui_state, UiState,
+};
+
+/// A press of the mouse buttons on the board
+#[derive(Clone, Copy, Debug, PartialEq)]
+enum MousePress {
+    /// A single button pressed on a square
+    Single(MouseButton, IndexType),
+    /// The chord button or the reveal and flag buttons pressed together, chords the square under the mouse on release
+    Chord,
+    /// A chord made with the reveal and flag buttons that is waiting for the last button to be released
+    ChordReleased,
+}
 
-#[allow(unused_imports)]
-use cgmath::prelude::*;
-use log::trace;
-use rand::prelude::*;
+/// What the game over overlay shows about a finished game
+#[derive(Clone, Debug)]
+pub struct GameSummary {
+    pub has_won: bool,
+    pub seed: u64,
+    /// Games where undo was used are not counted in any records
+    pub used_undo: bool,
+    /// Games where hints were used are not counted in any records either
+    pub hints: u32,
+    /// Neither are games where the analysis was shown
+    pub used_analysis: bool,
+    /// Or games on a board loaded from a file since the mines are not random
+    pub loaded: bool,
+    pub metrics: Metrics,
+    /// The board and start date of a won game that made it into the best times
+    pub new_best: Option<(BoardKey, u64)>,
+    /// The mines of the board so it can be exported, `None` for loaded boards since they already have a file
+    pub layout: Option<Layout>,
+    /// Seconds since the unix epoch when the game started, used to name the exported board
+    pub started: u64,
+}
 
-use crate::{config::GameConfig, err_here, error::LocatedError, state::*};
+impl GameSummary {
+    /// The directory in the user data directory where the boards of finished games are exported
+    const BOARD_DIRECTORY: &'static str = "boards";
 
-use super::ui_state;
+    /// Write the mines of the board to the boards directory so the board can be played again.
+    /// Loaded boards are not written again
+    pub fn export_board(&self, ctx: &Context) -> Result<(), LocatedError> {
+        let layout = match &self.layout {
+            Some(layout) => layout,
+            None => return Ok(()),
+        };
 
-//Types
-/// Used for points
-type Point2 = cgmath::Point2<f32>;
-/// # Index Type
-/// The type used for indices
-type IndexType = usize;
+        let directory = filesystem::user_data_dir(ctx).join(Self::BOARD_DIRECTORY);
+        fs::create_dir_all(&directory).map_err(err_here!())?;
 
-/// The state of a square   
-/// A square can either be closed and the bool states wether the player has set a flag on the square
-/// or it can be open and then the number represents the number of neighboring mines
-#[derive(Clone, Debug, PartialEq)]
-enum SquareState {
-    Closed(bool),
-    Open(u8),
-}
+        let path = directory.join(format!("board-{}-{}.txt", self.started, self.seed));
+        layout.write(&path)?;
 
-/// The progress of the current game
-enum Progress {
-    InGame,
-    Lost,
-    Won,
-    GameOver,
+        info!("Exported board to {:?}", path);
+        Ok(())
+    }
 }
 
-/// The main game state that runs the game
+/// The main game state that draws the board and passes the input to it
 pub struct GameState {
     game_config: GameConfig,
-    grid: Vec<SquareState>,
-    mines: std::collections::HashSet<usize>,
-    flag_image: graphics::Image,
-    number_image: graphics::Image,
-    mine_image: graphics::Image,
-    square: graphics::Mesh,
+    board: Board,
+    /// The mines of a board loaded from a file, every new game is played on it
+    layout: Option<Layout>,
+    history: History,
+    replay: Replay,
+    view: BoardView,
     mouse_index: Option<IndexType>,
-    mouse_press: Option<(ggez::input::mouse::MouseButton, IndexType)>,
-    progress: Progress,
+    mouse_press: Option<MousePress>,
+    /// The square picked with the keyboard, hidden when the mouse is moved
+    cursor: Option<IndexType>,
+    /// The hint shown until the board changes
+    hint: Option<Hint>,
+    /// The hint being looked for on another thread
+    hint_search: Option<HintSearch>,
+    /// The amount of hints shown in this game
+    hints: u32,
+    /// The chance of a mine on every square, drawn over the board while it is shown
+    analysis: Option<Analysis>,
+    used_analysis: bool,
+    hud: Hud,
+    font: graphics::Font,
+    elapsed: Duration,
+    /// The timer is stopped and the board is hidden while the game is paused
+    paused: bool,
+    restart: bool,
+    /// Restart with the mines of this board instead of a new one
+    same_board: bool,
+    /// Go back to the main menu in the next update
+    to_menu: bool,
+    game_over: bool,
 }
 
 impl GameState {
-    /// create a new game state
-    pub fn new(ctx: &mut Context, game_config: GameConfig) -> GameResult<Self> {
-        let grid =
-            vec![SquareState::Closed(false); game_config.game_size.0 * game_config.game_size.1];
-        let mines = std::collections::HashSet::<usize>::new();
+    /// create a new game state.
+    /// The mines are generated from the seed in the config or from a random seed if the config does not have one.
+    /// The seed is taken out of the config so games started again from this one get random seeds
+    pub fn new(ctx: &mut Context, mut game_config: GameConfig) -> GameResult<Self> {
+        let seed = game_config.seed.take().unwrap_or_else(rand::random);
+        Self::with_seed(ctx, game_config, seed)
+    }
 
-        let flag_image = graphics::Image::new(ctx, "\\flag.png")?;
-        let number_image = graphics::Image::new(ctx, "\\spr_numbers.png")?;
-        let mine_image = graphics::Image::new(ctx, "\\mine.png")?;
+    /// create a new game state where the mines are generated from the given seed
+    pub fn with_seed(ctx: &mut Context, game_config: GameConfig, seed: u64) -> GameResult<Self> {
+        let board = Board::new(game_config.game_size, game_config.number_of_mines, seed)
+            .set_generation(game_config.generation)
+            .set_win_condition(game_config.win_condition)
+            .set_topology(game_config.topology)
+            .set_question_marks(game_config.question_marks);
+
+        let replay = Replay::new(game_config.clone(), seed);
+        Self::with_board(
+            ctx,
+            game_config,
+            board,
+            History::new(),
+            replay,
+            Duration::default(),
+        )
+    }
+
+    /// create a new game state where the mines are placed from the layout.
+    /// The size and the amount of mines in the config have to be the ones of the layout
+    pub fn with_layout(
+        ctx: &mut Context,
+        game_config: GameConfig,
+        layout: Layout,
+    ) -> GameResult<Self> {
+        let board = Board::new(game_config.game_size, game_config.number_of_mines, 0)
+            .set_win_condition(game_config.win_condition)
+            .set_topology(game_config.topology)
+            .set_question_marks(game_config.question_marks)
+            .set_layout(&layout);
+
+        let replay = Replay::new(game_config.clone(), 0).set_layout(layout.clone());
+        let mut state = Self::with_board(
+            ctx,
+            game_config,
+            board,
+            History::new(),
+            replay,
+            Duration::default(),
+        )?;
+        state.layout = Some(layout);
+        Ok(state)
+    }
+
+    /// create a new game state on the mines of `board` with every square closed again.
+    /// Unlike a loaded layout the game counts for the records and playing again gives a new board
+    pub fn with_same_board(
+        ctx: &mut Context,
+        game_config: GameConfig,
+        board: &Board,
+    ) -> GameResult<Self> {
+        let board = board.restart();
+
+        let mut replay = Replay::new(game_config.clone(), board.seed());
+        if let Some(layout) = Layout::from_board(&board) {
+            replay = replay.set_layout(layout);
+        }
+        Self::with_board(
+            ctx,
+            game_config,
+            board,
+            History::new(),
+            replay,
+            Duration::default(),
+        )
+    }
+
+    /// Continue a saved game
+    pub fn from_save(ctx: &mut Context, save: SaveGame) -> GameResult<Self> {
+        let layout = save.replay.layout.clone().filter(|_| save.loaded);
+        let mut state = Self::with_board(
+            ctx,
+            save.game_config,
+            save.board,
+            save.history,
+            save.replay,
+            save.elapsed,
+        )?;
+        state.hints = save.hints;
+        state.used_analysis = save.used_analysis;
+        state.layout = layout;
+        Ok(state)
+    }
 
-        let color = graphics::WHITE;
-        let rect = graphics::Rect::new(0.0, 0.0, game_config.square_size, game_config.square_size);
-        let square = graphics::Mesh::new_rectangle(ctx, graphics::DrawMode::fill(), rect, color)?;
+    /// create a game state playing on the board where `elapsed` has already passed
+    fn with_board(
+        ctx: &mut Context,
+        game_config: GameConfig,
+        board: Board,
+        history: History,
+        replay: Replay,
+        elapsed: Duration,
+    ) -> GameResult<Self> {
+        let view = BoardView::new(ctx, &game_config, Self::board_offset(&game_config))?;
+
+        let hud = Hud::new(ctx, Self::screen_size(&game_config).0)
+            .map_err(|e| ggez::GameError::ResourceLoadError(e.to_string()))?;
+        let font = graphics::Font::new(ctx, "/Kenney_Future.ttf")?;
 
         Ok(GameState {
             game_config,
-            grid,
-            mines,
-            flag_image,
-            number_image,
-            mine_image,
-            square,
+            board,
+            layout: None,
+            history,
+            replay,
+            view,
             mouse_index: None,
             mouse_press: None,
-            progress: Progress::InGame,
+            cursor: None,
+            hint: None,
+            hint_search: None,
+            hints: 0,
+            analysis: None,
+            used_analysis: false,
+            hud,
+            font,
+            elapsed,
+            paused: false,
+            restart: false,
+            same_board: false,
+            to_menu: false,
+            game_over: false,
         })
     }
 
-    /// Converts from a linear index to a 2 dimensional point.   
-    ///
-    /// returns:   
-    /// x = index % game width   
-    /// y = index / game width
-    fn index_to_point(&self, i: IndexType) -> cgmath::Vector2<i32> {
-        cgmath::Vector2::new(
-            (i % self.game_config.game_size.0) as i32,
-            (i / self.game_config.game_size.0) as i32,
-        )
+    /// Make a move and remember it so it can be undone and replayed
+    fn make_move(&mut self, player_move: Move) {
+        self.clear_hint();
+        self.history.apply(&mut self.board, player_move);
+        self.replay
+            .record(self.elapsed, ReplayAction::Move(player_move));
     }
 
-    /// convert from a point to a index
-    ///   
-    /// returns:   
-    /// x + y * game width
-    fn point_to_index(&self, point: cgmath::Vector2<i32>) -> usize {
-        point.x as usize + point.y as usize * self.game_config.game_size.0
-    }
-
-    /// Gets the indices for all of the neighbors to a square
-    fn get_neighbors(&self, index: usize) -> [Option<usize>; 8] {
-        let point = self.index_to_point(index);
-        let mut i = 0;
-        let mut neighbors = [Option::<usize>::None; 8];
-        //Loop through all neighbors
-        for x in -1..2 {
-            for y in -1..2 {
-                //Skip the middle
-                if x == 0 && y == 0 {
-                    continue;
-                }
-
-                let current_point = point + cgmath::vec2(x, y);
+    /// Make the action bound to a key or mouse button, moves are made on the square at `index`.
+    /// Only pausing, restarting and going to the menu work while the game is paused
+    fn perform(&mut self, action: Action, index: Option<IndexType>) {
+        if self.paused && !matches!(action, Action::Pause | Action::Restart | Action::Menu) {
+            return;
+        }
 
-                if current_point.x < 0 || current_point.y < 0 {
-                    continue;
-                }
+        match (action, index) {
+            (Action::Reveal, Some(index)) => self.open_square(index),
+            (Action::Flag, Some(index)) => self.make_move(Move::Flag(index)),
+            (Action::Chord, Some(index)) => self.make_move(Move::Chord(index)),
+            (Action::Reveal, None) | (Action::Flag, None) | (Action::Chord, None) => {}
+            (Action::Restart, _) => self.restart = true,
+            (Action::Pause, _) => self.paused = !self.paused,
+            (Action::Undo, _) => self.undo(),
+            (Action::Redo, _) => self.redo(),
+            (Action::Menu, _) => self.to_menu = true,
+            (Action::Hint, _) => self.toggle_hint(),
+            (Action::Analysis, _) => self.toggle_analysis(),
+        }
+    }
 
-                if current_point.x >= self.game_config.game_size.0 as i32
-                    || current_point.y >= self.game_config.game_size.1 as i32
-                {
-                    continue;
-                }
+    /// Show or hide the chance of a mine on every square.
+    /// Showing it keeps the game out of the records
+    fn toggle_analysis(&mut self) {
+        if self.analysis.take().is_none() {
+            self.analysis = Some(Analysis::new(self.font));
+            self.used_analysis = true;
+        }
+    }
 
-                neighbors[i] = Some(self.point_to_index(current_point));
-                i += 1;
-            }
+    /// Start looking for a hint or hide the one that is shown.
+    /// Every hint shown is counted and keeps the game out of the records
+    fn toggle_hint(&mut self) {
+        if self.hint.is_some() || self.hint_search.is_some() {
+            self.clear_hint();
+            return;
         }
 
-        neighbors
+        self.hint_search = Some(HintSearch::start(&self.board));
     }
 
-    /// Counts the amount of neighboring squares with mines
-    fn count_neighbors(&self, i: usize) -> u8 {
-        let mut count = 0;
-        let neighbors = self.get_neighbors(i);
-
-        for neighbor in neighbors.iter() {
-            if let Some(index) = neighbor {
-                if self.mines.contains(index) {
-                    count += 1;
-                }
+    /// Show the hint once the search for it is done
+    fn update_hint(&mut self) {
+        if let Some(hint) = self.hint_search.as_ref().and_then(HintSearch::finished) {
+            self.hint_search = None;
+            self.hint = hint;
+            if self.hint.is_some() {
+                self.hints += 1;
             }
         }
+    }
 
-        count
-    }
-
-    /// Draw the squares
-    fn draw_squares(&self, ctx: &mut ggez::Context) -> GameResult<()> {
-        let colors = &self.game_config.colors;
-
-        for i in 0..self.grid.len() {
-            let point = self.index_to_point(i);
-            let v = self.game_config.square_size * Point2::new(point.x as f32, point.y as f32);
-
-            let mut params = graphics::DrawParam::new();
-            params.dest = v.into();
-
-            match self.grid[i] {
-                SquareState::Closed(flag) => {
-                    // if the mouse is pressed the square it was pressed on is the selected one
-                    // otherwise it is the square that the mouse is over
-                    params.color = if let Some((_, index)) = self.mouse_press {
-                        if index == i {
-                            colors.selected_square.into()
-                        } else {
-                            colors.square.into()
-                        }
-                    } else if let Some(index) = self.mouse_index {
-                        if index == i {
-                            colors.selected_square.into()
-                        } else {
-                            colors.square.into()
-                        }
-                    } else {
-                        colors.square.into()
-                    };
-
-                    graphics::draw(ctx, &self.square, params)?;
-
-                    if flag {
-                        params.color = graphics::WHITE;
-                        let scale = self.game_config.square_size / self.flag_image.dimensions().w;
-                        params.scale = ggez::mint::Vector2 { x: scale, y: scale };
-                        graphics::draw(ctx, &self.flag_image, params)?;
-                    }
-                }
-                SquareState::Open(mine_count) => {
-                    if self.mines.contains(&i) {
-                        params.color = graphics::WHITE;
-                        graphics::draw(ctx, &self.mine_image, params)?;
-                    } else if mine_count > 0 {
-                        let origin_point =
-                            cgmath::vec2((mine_count % 3) as f32, (mine_count / 3) as f32);
-                        let origin_pos: cgmath::Vector2<f32> =
-                            origin_point * self.game_config.square_size / 96.0;
-                        params.src =
-                            graphics::Rect::new(origin_pos.x, origin_pos.y, 1.0 / 3.0, 1.0 / 3.0);
-
-                        graphics::draw(ctx, &self.number_image, params)?;
-                    }
-                }
-            }
-        }
+    /// Hide the hint and stop looking for one since the board has changed
+    fn clear_hint(&mut self) {
+        self.hint = None;
+        self.hint_search = None;
+    }
 
-        Ok(())
+    /// Returns true if the button is bound to the action
+    fn mouse_is(&self, button: MouseButton, action: Action) -> bool {
+        self.game_config.bindings.mouse_action(button) == Some(action)
     }
 
-    /// Opens a square and checks the amount of neighboring mines   
-    /// If mines aren't generated it will generate them first
-    fn open(&mut self, index: IndexType) {
-        if self.mines.is_empty() {
-            self.generate_mines(self.game_config.number_of_mines, index);
+    /// Open a closed square or chord an open one
+    fn open_square(&mut self, index: IndexType) {
+        if let SquareState::Open(_) = self.board.square(index) {
+            self.make_move(Move::Chord(index))
+        } else {
+            self.make_move(Move::Reveal(index))
         }
+    }
 
-        let neighbor_count = self.count_neighbors(index);
-        self.grid[index] = SquareState::Open(neighbor_count);
+    /// Move the keyboard cursor one square, it stops at the edges of the board.
+    /// The cursor starts from the square under the mouse or the middle of the board
+    fn move_cursor(&mut self, dx: i32, dy: i32) {
+        let start = self.cursor.or(self.mouse_index).unwrap_or_else(|| {
+            let (width, height) = self.board.size();
+            self.board
+                .point_to_index(cgmath::vec2(width as i32 / 2, height as i32 / 2))
+        });
+
+        let point = self.board.index_to_point(start) + cgmath::vec2(dx, dy);
+        self.cursor = if self.board.contains_point(point) {
+            Some(self.board.point_to_index(point))
+        } else {
+            Some(start)
+        };
+    }
 
-        if self.mines.contains(&index) {
-            self.progress = Progress::Lost;
-            return;
+    /// Undo the last move, the move that lost the game can only be undone in practice mode
+    fn undo(&mut self) {
+        self.clear_hint();
+        if self
+            .history
+            .undo(&mut self.board, self.game_config.practice)
+        {
+            self.replay.record(self.elapsed, ReplayAction::Undo);
         }
+    }
 
-        if self.mines == self.get_closed_squares() {
-            self.progress = Progress::Won;
+    /// Redo the last undone move
+    fn redo(&mut self) {
+        self.clear_hint();
+        if self.history.redo(&mut self.board) {
+            self.replay.record(self.elapsed, ReplayAction::Redo);
         }
+    }
 
-        if neighbor_count > 0 {
-            return;
+    /// Save the game if it is in progress so it can be continued later
+    fn save(&self, ctx: &Context) -> Result<(), LocatedError> {
+        if self.board.is_started() && self.board.status() == Status::InGame {
+            SaveGame::new(
+                self.game_config.clone(),
+                self.board.clone(),
+                self.history.clone(),
+                self.replay.clone(),
+                self.elapsed,
+            )
+            .set_hints(self.hints)
+            .set_used_analysis(self.used_analysis)
+            .set_loaded(self.layout.is_some())
+            .write(ctx)?;
         }
 
-        for &neighbor in self.get_neighbors(index).iter() {
-            if let Some(index) = neighbor {
-                if let SquareState::Closed(_b) = self.grid[index] {
-                    self.open(index)
-                }
+        Ok(())
+    }
+
+    /// Draw a cover over the board with the text paused
+    fn draw_paused(&self, ctx: &mut Context) -> GameResult {
+        let (width, height) = Self::screen_size(&self.game_config);
+        let rect = graphics::Rect::new(0.0, Hud::HEIGHT, width, height - Hud::HEIGHT);
+        let cover = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            rect,
+            self.game_config.colors.square.into(),
+        )?;
+        graphics::draw(ctx, &cover, graphics::DrawParam::default())?;
+
+        let mut text = graphics::Text::new("Paused");
+        text.set_font(self.font, graphics::Scale::uniform(32.0));
+        let position = cgmath::point2(
+            (width - text.width(ctx) as f32) / 2.0,
+            rect.y + (rect.h - text.height(ctx) as f32) / 2.0,
+        );
+        graphics::draw(ctx, &text, graphics::DrawParam::default().dest(position))
+    }
+
+    /// Draw a line at the bottom of the board saying that a hint is being looked for
+    fn draw_hint_search(&self, ctx: &mut Context) -> GameResult {
+        let height = Self::screen_size(&self.game_config).1;
+        let mut text = graphics::Text::new("Looking for a hint");
+        text.set_font(self.font, graphics::Scale::uniform(12.0));
+        let position = cgmath::point2(8.0, height - text.height(ctx) as f32 - 8.0);
+        graphics::draw(ctx, &text, graphics::DrawParam::default().dest(position))
+    }
+
+    /// Draw an outline around the hinted square and the explanation over the half of the board away from it
+    fn draw_hint(&self, ctx: &mut Context, hint: &Hint) -> GameResult {
+        let rect = self.view.square_rect(&self.board, hint.index());
+        let corners = self.view.square_corners(&self.board, hint.index());
+        let color = match hint {
+            Hint::Deduction(deduction) if deduction.is_mine => {
+                self.game_config.colors.mine_square.into()
             }
-        }
+            Hint::Guess { probability, .. } if *probability > 0.0 => {
+                graphics::Color::from_rgb(255, 200, 0)
+            }
+            _ => graphics::Color::from_rgb(50, 205, 50),
+        };
+        let outline =
+            graphics::Mesh::new_polygon(ctx, graphics::DrawMode::stroke(3.0), &corners, color)?;
+        graphics::draw(ctx, &outline, graphics::DrawParam::default())?;
+
+        let (width, height) = Self::screen_size(&self.game_config);
+        let mut text = graphics::Text::new(hint.explanation());
+        text.set_font(self.font, graphics::Scale::uniform(12.0));
+        text.set_bounds(
+            cgmath::point2(width - 16.0, f32::INFINITY),
+            graphics::Align::Left,
+        );
+
+        let banner_height = text.height(ctx) as f32 + 16.0;
+        let board_middle = Hud::HEIGHT + (height - Hud::HEIGHT) / 2.0;
+        let y = if rect.y < board_middle {
+            height - banner_height
+        } else {
+            Hud::HEIGHT
+        };
+
+        let banner = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            graphics::Rect::new(0.0, y, width, banner_height),
+            graphics::Color::new(0.0, 0.0, 0.0, 0.75),
+        )?;
+        graphics::draw(ctx, &banner, graphics::DrawParam::default())?;
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::default().dest(cgmath::point2(8.0, y + 8.0)),
+        )
     }
 
-    /// Generate mines in random slots
-    fn generate_mines(&mut self, number_of_mines: IndexType, graced_index: IndexType) {
-        let mut rng = rand::thread_rng();
-        let dist = rand::distributions::Uniform::new(0, self.grid.len());
+    /// Record the finished game in the statistics and summarize it for the game over overlay.
+    /// Games where undo, hints or the analysis were used and games on loaded boards are not recorded
+    fn finish_game(&self, ctx: &mut Context) -> GameSummary {
+        let mut summary = GameSummary {
+            has_won: self.board.status() == Status::Won,
+            seed: self.board.seed(),
+            used_undo: self.history.used_undo(),
+            hints: self.hints,
+            used_analysis: self.used_analysis,
+            loaded: self.layout.is_some(),
+            metrics: Metrics::new(&self.board, self.replay.clicks(), self.elapsed),
+            new_best: None,
+            layout: Layout::from_board(&self.board).filter(|_| self.layout.is_none()),
+            started: self.replay.started,
+        };
 
-        let mut result = collections::HashSet::new();
+        if summary.used_undo || summary.hints > 0 || summary.used_analysis || summary.loaded {
+            return summary;
+        }
 
-        let mut tries = 0;
-        while result.len() < number_of_mines {
-            let next = dist.sample(&mut rng);
+        let key = BoardKey::new(&self.game_config);
+        let record = GameRecord {
+            won: summary.has_won,
+            metrics: summary.metrics,
+            date: self.replay.started,
+        };
 
-            if next != graced_index {
-                result.insert(next);
-            }
-            tries += 1;
+        let recorded = Statistics::load(ctx).and_then(|mut statistics| {
+            let new_best = statistics.record(&key, &record);
+            statistics.save(ctx).map(|_| new_best)
+        });
+
+        match recorded {
+            Ok(true) => summary.new_best = Some((key, record.date)),
+            Ok(false) => {}
+            Err(e) => error!("Could not save the statistics: {}", e),
         }
 
-        trace!("Mines generated at {:?} after {} tries", result, tries);
-        self.mines = result;
+        summary
     }
 
-    /// Get all the indices for the squares that are still closed
-    fn get_closed_squares(&self) -> HashSet<usize> {
-        let iter = self.grid.iter()
-            .enumerate()
-            .filter_map(|s|{
-                if let SquareState::Closed(_) = s.1  {
-                    Some(s.0)
-                } else {
-                    None
-                }
-            });
-
-        iter.collect()
+    /// The size of the screen needed to fit the hud and the board.
+    /// The screen is at least as wide as the narrowest board of squares so the hud fits
+    pub fn screen_size(game_config: &GameConfig) -> (f32, f32) {
+        let (width, height) = BoardView::board_size(game_config);
+        let min_width = GameConfig::MIN_SIZE as f32 * game_config.square_size;
+        (width.max(min_width), height + Hud::HEIGHT)
     }
 
-    fn get_flagged_squares(&self) -> HashSet<usize> {
-        let iter = self.grid.iter()
-            .enumerate()
-            .filter_map(|s|{
-                if let SquareState::Closed(flagged) = s.1 {
-                    if *flagged {
-                        return Some(s.0);
-                    }
-                }
+    /// Where the top left corner of the board is drawn, below the hud and centered on the screen
+    pub fn board_offset(game_config: &GameConfig) -> cgmath::Vector2<f32> {
+        let board_width = BoardView::board_size(game_config).0;
+        let screen_width = Self::screen_size(game_config).0;
+        cgmath::vec2((screen_width - board_width) / 2.0, Hud::HEIGHT)
+    }
 
-                None
-            });
+    /// The squares that should be drawn as selected.
+    /// A pressed button selects the square it was pressed on and a held chord selects the whole neighborhood,
+    /// otherwise it is the keyboard cursor or the square that the mouse is over
+    fn selected_squares(&self) -> Vec<IndexType> {
+        let neighborhood = |index: IndexType| {
+            let mut squares: Vec<IndexType> = self
+                .board
+                .get_neighbors(index)
+                .iter()
+                .flatten()
+                .copied()
+                .collect();
+            squares.push(index);
+            squares
+        };
 
-        iter.collect()
+        match self.mouse_press {
+            Some(MousePress::Chord) => self.mouse_index.map(neighborhood).unwrap_or_default(),
+            Some(MousePress::ChordReleased) => vec![],
+            Some(MousePress::Single(button, index))
+                if self.mouse_is(button, Action::Reveal)
+                    && matches!(self.board.square(index), SquareState::Open(_)) =>
+            {
+                neighborhood(index)
+            }
+            Some(MousePress::Single(_, index)) => vec![index],
+            None if self.paused => vec![],
+            None => self.cursor.or(self.mouse_index).into_iter().collect(),
+        }
     }
 }
 
 impl State for GameState {
     /// Main update
     fn update(&mut self, ctx: &mut ggez::Context) -> Result<UpdateResult, LocatedError> {
-        //update delta time
-        let _dt = ggez::timer::delta(ctx);
-
-        match self.progress {
-            Progress::InGame => {
-            }
-            Progress::Lost => {
-                self.progress = Progress::GameOver;
-                let new_state = ui_state::UiState::create_game_over_state(ctx, false)?;
-                return Ok(UpdateResult::Push(Box::new(new_state)));
+        if self.restart {
+            let game_config = self.game_config.clone();
+            let new_state = match &self.layout {
+                Some(layout) => GameState::with_layout(ctx, game_config, layout.clone()),
+                None if self.same_board => {
+                    GameState::with_same_board(ctx, game_config, &self.board)
+                }
+                None => GameState::new(ctx, game_config),
             }
-            Progress::Won => {
-                self.progress = Progress::GameOver;
-                let new_state = ui_state::UiState::create_game_over_state(ctx, true)?;
+            .map_err(err_here!())?;
+            return Ok(UpdateResult::Swap(Box::new(new_state)));
+        }
+
+        // The game is saved so it can be continued from the menu
+        if self.to_menu {
+            self.save(ctx)?;
+            set_screen_size(ctx, UiState::MENU_SIZE)?;
+            return Ok(UpdateResult::Pop);
+        }
+
+        if let Some(analysis) = &mut self.analysis {
+            analysis
+                .update(ctx, &self.board, &self.view)
+                .map_err(err_here!())?;
+        }
+        self.update_hint();
+
+        // The timer runs from the first reveal until the game is over
+        let dt = ggez::timer::delta(ctx);
+        if self.board.is_started() && self.board.status() == Status::InGame && !self.paused {
+            self.elapsed += dt;
+        }
+
+        match self.board.status() {
+            // The move that ended the game can be undone in practice mode
+            Status::InGame => self.game_over = false,
+            _ if !self.game_over => {
+                self.game_over = true;
+
+                // A replay that can not be saved should not stop the game
+                if let Err(e) = self.replay.write(ctx) {
+                    error!("Could not save the replay: {}", e);
+                }
+
+                let summary = self.finish_game(ctx);
+                let new_state = ui_state::UiState::create_game_over_state(ctx, &summary)?;
                 return Ok(UpdateResult::Push(Box::new(new_state)));
             }
-            Progress::GameOver => {
-                let new_state =
-                    GameState::new(ctx, self.game_config.clone()).map_err(err_here!())?;
-                return Ok(UpdateResult::Swap(Box::new(new_state)));
-            }
+            // The finished board stays until the player picks what to do next
+            _ => {}
         }
 
         Ok(UpdateResult::Block)
     }
 
-    /// Draw the playing grid
+    /// The game over overlay tells the game what to do next
+    fn message_event(
+        &mut self,
+        ctx: &mut ggez::Context,
+        message: Message,
+    ) -> Result<(), LocatedError> {
+        match message {
+            Message::PlayAgain => self.restart = true,
+            Message::ReplayBoard => {
+                self.restart = true;
+                self.same_board = true;
+            }
+            Message::ChangeDifficulty => self.to_menu = true,
+            Message::Quit => ggez::event::quit(ctx),
+        }
+
+        Ok(())
+    }
+
+    /// Save the game if it is in progress so it can be continued the next time
+    fn quit_event(&mut self, ctx: &mut ggez::Context) -> Result<(), LocatedError> {
+        self.save(ctx)
+    }
+
+    /// Draw the hud and the playing grid, the grid is covered while the game is paused
     fn draw(&mut self, ctx: &mut ggez::Context) -> ggez::GameResult<()> {
-        self.draw_squares(ctx)?;
+        self.hud
+            .draw(ctx, &self.board, self.elapsed)
+            .map_err(|e| ggez::GameError::RenderError(e.to_string()))?;
+
+        if self.paused {
+            self.draw_paused(ctx)?;
+        } else {
+            self.view.draw(ctx, &self.board, &self.selected_squares())?;
+
+            if let Some(analysis) = &self.analysis {
+                if self.board.status() == Status::InGame {
+                    analysis.draw(ctx, &self.board, cgmath::point2(8.0, Hud::HEIGHT + 8.0))?;
+                }
+            }
+
+            if let Some(hint) = &self.hint {
+                self.draw_hint(ctx, hint)?;
+            } else if self.hint_search.is_some() {
+                self.draw_hint_search(ctx)?;
+            }
+        }
         Ok(())
     }
 
@@ -338,78 +668,127 @@ impl State for GameState {
         _dx: f32,
         _dy: f32,
     ) -> ggez::GameResult<EventResult> {
-        // Convert the mouse position to a position in the playing grid
-        let point = cgmath::Vector2::<i32>::new(
-            (x / self.game_config.square_size) as i32,
-            (y / self.game_config.square_size) as i32,
-        );
-
-        // Update the mouse index
-        self.mouse_index = if point.x >= 0
-            && point.y >= 0
-            && point.x < self.game_config.game_size.0 as i32
-            && point.y < self.game_config.game_size.1 as i32
-        {
-            Some(self.point_to_index(point))
-        } else {
-            None
-        };
+        self.mouse_index = self.view.index_at(&self.board, x, y);
+        self.cursor = None;
 
         Ok(EventResult::Block)
     }
 
-    /// Triggered when the mouse is released and is the end of a mouse press.   
-    /// If the mouse is released on the same square as it was pressed it will call ```open```
+    /// Triggered when the mouse is released and is the end of a mouse press.
+    /// Releasing the left button over the face restarts the game.
+    /// If the mouse is released on the same square as it was pressed it makes the action bound to the button.
+    /// Revealing an open square or releasing a chord opens the neighbors if the flags match the number
     fn mouse_button_up_event(
         &mut self,
         _ctx: &mut ggez::Context,
-        button: ggez::input::mouse::MouseButton,
-        _x: f32,
-        _y: f32,
+        button: MouseButton,
+        x: f32,
+        y: f32,
     ) -> ggez::GameResult<EventResult> {
-        // Get and unwrap mouse press and mouse index
-        if let (Some((press_button, press_index)), Some(mouse_index)) =
-            (self.mouse_press, self.mouse_index)
-        {
-            // If the mouse is released on the same square it was pressed it will active click
-            if button == press_button && mouse_index == press_index {
-                trace!("Mouse pressed on index {:?}", mouse_index);
-                match button {
-                    ggez::event::MouseButton::Left => self.open(press_index),
-                    ggez::event::MouseButton::Right => {
-                        // If right button is pressed we toggle the flag
-                        if let SquareState::Closed(flagged) = self.grid[press_index as usize] {
-                            self.grid[press_index as usize] = SquareState::Closed(!flagged);
-
-                            if self.mines == self.get_flagged_squares() {
-                                self.progress = Progress::Won;
-                            }
-                        }
+        // Clicking the face in the hud restarts the game
+        if button == MouseButton::Left && self.hud.face_rect().contains(cgmath::point2(x, y)) {
+            self.restart = true;
+        }
+
+        let action = self.game_config.bindings.mouse_action(button);
+
+        self.mouse_press = match self.mouse_press {
+            Some(MousePress::Single(press_button, press_index)) => {
+                // If the mouse is released on the same square it was pressed it will active click
+                if button == press_button && self.mouse_index == Some(press_index) {
+                    trace!("Mouse pressed on index {:?}", press_index);
+                    if let Some(action) = action {
+                        self.perform(action, Some(press_index));
                     }
-                    ggez::event::MouseButton::Middle => {}
-                    ggez::event::MouseButton::Other(_) => {}
                 }
+                None
             }
-        }
+            Some(MousePress::Chord) => {
+                if let Some(index) = self.mouse_index {
+                    trace!("Chord on index {:?}", index);
+                    self.perform(Action::Chord, Some(index));
+                }
 
-        self.mouse_press = None;
+                // A reveal and flag chord waits for the other button before a new press can start
+                if action == Some(Action::Chord) {
+                    None
+                } else {
+                    Some(MousePress::ChordReleased)
+                }
+            }
+            Some(MousePress::ChordReleased) => None,
+            // Buttons for actions that do not need a square work anywhere
+            None => {
+                match action {
+                    Some(Action::Reveal) | Some(Action::Flag) | Some(Action::Chord) | None => {}
+                    Some(action) => self.perform(action, None),
+                }
+                None
+            }
+        };
 
         Ok(EventResult::Block)
     }
 
-    /// Triggered when the mouse button is pressed down.   
+    /// Triggered when the mouse button is pressed down.
     /// Saves which square the mouse was over when the button was pressed.
+    /// Pressing the chord button or both the reveal and flag buttons starts a chord
     fn mouse_button_down_event(
         &mut self,
         _ctx: &mut ggez::Context,
-        button: ggez::input::mouse::MouseButton,
+        button: MouseButton,
         _x: f32,
         _y: f32,
     ) -> ggez::GameResult<EventResult> {
-        // When a mouse button is pressed set the mouse press to the button and index of the square the mouse is over
-        if let Some(index) = self.mouse_index {
-            self.mouse_press = Some((button, index))
-        }
+        let both_pressed = |first: MouseButton, first_action: Action, second_action: Action| {
+            self.mouse_is(first, first_action) && self.mouse_is(button, second_action)
+        };
+
+        self.mouse_press = match self.mouse_press {
+            _ if self.mouse_is(button, Action::Chord) => Some(MousePress::Chord),
+            Some(MousePress::Single(first, _))
+                if both_pressed(first, Action::Reveal, Action::Flag)
+                    || both_pressed(first, Action::Flag, Action::Reveal) =>
+            {
+                Some(MousePress::Chord)
+            }
+            // When a mouse button is pressed set the mouse press to the button and index of the square the mouse is over
+            _ => self
+                .mouse_index
+                .map(|index| MousePress::Single(button, index)),
+        };
+
         Ok(EventResult::Block)
     }
+
+    /// Keys bound to an action make it once when pressed.
+    /// The arrow keys and IJKL move the cursor and keep moving it while they are held,
+    /// keys bound to an action are not used to move.
+    /// Without a cursor the moves are made on the square under the mouse
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut ggez::Context,
+        keycode: KeyCode,
+        keymods: KeyMods,
+        repeat: bool,
+    ) -> ggez::GameResult<EventResult> {
+        if let Some(action) = self.game_config.bindings.key_action(keycode, keymods) {
+            if !repeat {
+                self.perform(action, self.cursor.or(self.mouse_index));
+            }
+            return Ok(EventResult::Block);
+        }
+
+        if keymods.contains(KeyMods::CTRL) || self.paused {
+            return Ok(EventResult::LetThrough);
+        }
+
+        match InputBindings::movement(keycode) {
+            Some((dx, dy)) => {
+                self.move_cursor(dx, dy);
+                Ok(EventResult::Block)
+            }
+            None => Ok(EventResult::LetThrough),
+        }
+    }
 }
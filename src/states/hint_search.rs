@@ -0,0 +1,54 @@
+use std::{
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        mpsc, Arc,
+    },
+    thread,
+};
+
+use log::trace;
+
+use crate::board::{Board, Hint};
+
+/// # Hint Search
+/// Looks for a hint on another thread so the game keeps running while the chances of
+/// a large board are worked out. The search is stopped when it is dropped
+pub struct HintSearch {
+    result: mpsc::Receiver<Option<Hint>>,
+    cancel: Arc<AtomicBool>,
+}
+
+impl HintSearch {
+    /// Start looking for a hint for the board
+    pub fn start(board: &Board) -> Self {
+        let (sender, result) = mpsc::channel();
+        let cancel = Arc::new(AtomicBool::new(false));
+
+        let board = board.clone();
+        let thread_cancel = cancel.clone();
+        thread::spawn(move || {
+            let hint = Hint::find_cancellable(&board, &thread_cancel);
+
+            if thread_cancel.load(Ordering::Relaxed) {
+                trace!("Stopped looking for an outdated hint");
+            } else {
+                // The search may have been stopped in the meantime
+                let _ = sender.send(hint);
+            }
+        });
+
+        HintSearch { result, cancel }
+    }
+
+    /// The hint once the search is done, `None` while it is still looking
+    pub fn finished(&self) -> Option<Option<Hint>> {
+        self.result.try_recv().ok()
+    }
+}
+
+impl Drop for HintSearch {
+    /// A hint for a board that changed is not needed anymore
+    fn drop(&mut self) {
+        self.cancel.store(true, Ordering::Relaxed);
+    }
+}
@@ -0,0 +1,109 @@
+use std::time::Duration;
+
+use ggez::{graphics, Context};
+
+use crate::{
+    board::{Board, Status},
+    err_here,
+    error::LocatedError,
+    sprite_sheet::SpriteSheet,
+    ui::{Element, Panel},
+};
+
+/// The sprite used behind the face
+const FACE_SPRITE: &str = "button06";
+
+/// # Hud
+/// The strip above the board showing the mines left, the face used to restart and the time played
+pub struct Hud {
+    sprite_sheet: SpriteSheet,
+    panel: Panel,
+    font: graphics::Font,
+    width: f32,
+}
+
+impl Hud {
+    /// The height of the hud in pixels
+    pub const HEIGHT: f32 = 64.0;
+
+    /// Create a hud that stretches over the given width
+    pub fn new(ctx: &mut Context, width: f32) -> Result<Self, LocatedError> {
+        let sprite_sheet = SpriteSheet::load(ctx, "/Ui/Spritesheet/colored_sheet.ron", "blue")?;
+        let panel = Panel::with_default_sprites(graphics::Rect::new(0.0, 0.0, width, Self::HEIGHT));
+        let font = graphics::Font::new(ctx, "/Kenney_Future.ttf").map_err(err_here!())?;
+
+        Ok(Hud {
+            sprite_sheet,
+            panel,
+            font,
+            width,
+        })
+    }
+
+    /// The rectangle covered by the face
+    pub fn face_rect(&self) -> graphics::Rect {
+        let size = self
+            .sprite_sheet
+            .get_sprite_pixel_size(FACE_SPRITE)
+            .unwrap_or_else(|| cgmath::vec2(Self::HEIGHT, Self::HEIGHT));
+
+        graphics::Rect::new(
+            (self.width - size.x) / 2.0,
+            (Self::HEIGHT - size.y) / 2.0,
+            size.x,
+            size.y,
+        )
+    }
+
+    /// Draw the hud for the board, `elapsed` is the time shown on the timer
+    pub fn draw(
+        &self,
+        ctx: &mut Context,
+        board: &Board,
+        elapsed: Duration,
+    ) -> Result<(), LocatedError> {
+        self.panel.draw(ctx, &self.sprite_sheet)?;
+
+        let text_y = (Self::HEIGHT - 32.0) / 2.0;
+
+        // Flags placed on safe squares still count so the counter can go below zero
+        let mines_left = board.number_of_mines() as i64 - board.get_flagged_squares().len() as i64;
+        let counter = self.counter_text(mines_left);
+        graphics::draw(
+            ctx,
+            &counter,
+            graphics::DrawParam::default().dest(cgmath::point2(16.0, text_y)),
+        )
+        .map_err(err_here!())?;
+
+        let face = match board.status() {
+            Status::InGame => ":)",
+            Status::Won => "B)",
+            Status::Lost => "X(",
+        };
+        let face_rect = self.face_rect();
+        Element::new_element(cgmath::point2(face_rect.x, face_rect.y))
+            .set_sprite(FACE_SPRITE)
+            .set_label(face, &self.font, &graphics::Scale::uniform(24.0))
+            .draw(ctx, &self.sprite_sheet)
+            .map_err(err_here!())?;
+
+        let timer = self.counter_text(elapsed.as_secs().min(999) as i64);
+        let timer_x = self.width - 16.0 - timer.width(ctx) as f32;
+        graphics::draw(
+            ctx,
+            &timer,
+            graphics::DrawParam::default().dest(cgmath::point2(timer_x, text_y)),
+        )
+        .map_err(err_here!())?;
+
+        Ok(())
+    }
+
+    /// A number padded to three digits like the counters on a classic mine sweeper
+    fn counter_text(&self, value: i64) -> graphics::Text {
+        let mut text = graphics::Text::new(format!("{:03}", value));
+        text.set_font(self.font, graphics::Scale::uniform(32.0));
+        text
+    }
+}
@@ -59,6 +59,24 @@ impl event::EventHandler for MainState {
                         ggez::event::quit(ctx)
                     }
 
+                    return Ok(());
+                }
+                state::UpdateResult::PopWith(message) => {
+                    self.state_stack.pop_front();
+
+                    match self.state_stack.front_mut() {
+                        Some(state) => {
+                            if let Err(e) = state.message_event(ctx, message) {
+                                error!("Encountered error in message event: {:?}", e);
+                                return Err(ggez::GameError::EventLoopError(e.to_string()));
+                            }
+                        }
+                        None => {
+                            info!("no states left");
+                            ggez::event::quit(ctx)
+                        }
+                    }
+
                     return Ok(());
                 }
             }
@@ -156,6 +174,38 @@ impl event::EventHandler for MainState {
         }
     }
 
+    /// Escape closes the game the same way as closing the window,
+    /// other keys go through the states until a state blocks it
+    fn key_down_event(
+        &mut self,
+        ctx: &mut Context,
+        keycode: ggez::input::keyboard::KeyCode,
+        keymods: ggez::input::keyboard::KeyMods,
+        repeat: bool,
+    ) {
+        if keycode == ggez::input::keyboard::KeyCode::Escape {
+            if !self.quit_event(ctx) {
+                ggez::event::quit(ctx);
+            }
+            return;
+        }
+
+        for state in &mut self.state_stack {
+            match state.key_down_event(ctx, keycode, keymods, repeat) {
+                Ok(r) => {
+                    if r == EventResult::Block {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!("Encountered error in key down event: {:?}", e);
+                    self.event_result = Err(e);
+                    break;
+                }
+            }
+        }
+    }
+
     fn key_up_event(
         &mut self,
         ctx: &mut Context,
@@ -177,4 +227,33 @@ impl event::EventHandler for MainState {
             }
         }
     }
+
+    /// Goes through the states and passes the typed character until a state blocks it
+    fn text_input_event(&mut self, ctx: &mut Context, character: char) {
+        for state in &mut self.state_stack {
+            match state.text_input_event(ctx, character) {
+                Ok(r) => {
+                    if r == EventResult::Block {
+                        break;
+                    }
+                }
+                Err(e) => {
+                    error!("Encountered error in text input event: {:?}", e);
+                    self.event_result = Err(e);
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Lets every state know that the game is closing
+    fn quit_event(&mut self, ctx: &mut Context) -> bool {
+        for state in &mut self.state_stack {
+            if let Err(e) = state.quit_event(ctx) {
+                error!("Encountered error in quit event: {:?}", e);
+            }
+        }
+
+        false
+    }
 }
@@ -1,7 +1,17 @@
+mod analysis;
+mod bindings_editor;
+mod board_view;
+mod custom_game;
 mod game_state;
+mod hint_search;
+mod hud;
 mod main_state;
+mod name_entry;
+mod replay_state;
+mod statistics_view;
 mod ui_state;
 
 pub use self::game_state::*;
 pub use self::main_state::MainState;
+pub use self::replay_state::ReplayState;
 pub use self::ui_state::UiState;
@@ -0,0 +1,79 @@
+use ggez::{graphics, Context, GameResult};
+
+use crate::{
+    error::LocatedError,
+    statistics::{BoardKey, Statistics},
+    ui::Element,
+};
+
+/// # Name Entry
+/// The name typed in for a new best time
+pub struct NameEntry {
+    key: BoardKey,
+    /// When the game with the best time started, used to find it in the best times
+    date: u64,
+    name: String,
+    font: graphics::Font,
+    position: cgmath::Point2<f32>,
+}
+
+impl NameEntry {
+    /// The longest name that fits in the game over overlay
+    pub const MAX_LENGTH: usize = 12;
+
+    /// Start entering a name for the best time of the game started at `date`, starting from `name`
+    pub fn new(
+        key: BoardKey,
+        date: u64,
+        name: String,
+        font: graphics::Font,
+        position: cgmath::Point2<f32>,
+    ) -> Self {
+        NameEntry {
+            key,
+            date,
+            name,
+            font,
+            position,
+        }
+    }
+
+    /// Add a typed character to the name
+    pub fn push(&mut self, character: char) {
+        if !character.is_control() && self.name.chars().count() < Self::MAX_LENGTH {
+            self.name.push(character);
+        }
+    }
+
+    /// Remove the last character of the name
+    pub fn pop(&mut self) {
+        self.name.pop();
+    }
+
+    /// Write the name to the best time in the statistics
+    pub fn save(&self, ctx: &mut Context) -> Result<(), LocatedError> {
+        let mut statistics = Statistics::load(ctx)?;
+        statistics.set_name(&self.key, self.date, self.name.trim());
+        statistics.save(ctx)
+    }
+
+    /// The name without the cursor, shown once it has been saved
+    pub fn into_label(self) -> Element {
+        Element::new_element(self.position).set_label(
+            &format!("Name: {}", self.name),
+            &self.font,
+            &graphics::Scale::uniform(16.0),
+        )
+    }
+
+    /// Draw the name with a cursor after it
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let mut text = graphics::Text::new(format!("Name: {}_", self.name));
+        text.set_font(self.font, graphics::Scale::uniform(16.0));
+        graphics::draw(
+            ctx,
+            &text,
+            graphics::DrawParam::default().dest(self.position),
+        )
+    }
+}
@@ -0,0 +1,326 @@
+use std::time::Duration;
+
+use ggez::{
+    event::{KeyCode, KeyMods, MouseButton},
+    graphics, Context, GameResult,
+};
+
+use crate::{
+    board::{Board, History},
+    config::GameConfig,
+    error::LocatedError,
+    replay::Replay,
+    screen::set_screen_size,
+    state::*,
+};
+
+use super::{board_view::BoardView, hud::Hud, GameState, UiState};
+
+/// The board and history after some action of the replay
+struct Snapshot {
+    /// The index of the next action to play from the snapshot
+    next_action: usize,
+    board: Board,
+    history: History,
+}
+
+/// # Replay State
+/// Plays a recorded game back on a board generated from the same seed.
+/// Space pauses, 1, 2 and 4 change the speed, the arrow keys or clicking the timeline moves through the game
+/// and backspace goes back to the menu
+pub struct ReplayState {
+    replay: Replay,
+    board: Board,
+    history: History,
+    view: BoardView,
+    hud: Hud,
+    font: graphics::Font,
+    /// How far into the game the replay is
+    position: Duration,
+    /// The index of the next action to play
+    next_action: usize,
+    /// The board at the start, after the mines were generated and at every `SNAPSHOT_INTERVAL` actions.
+    /// Seeking backwards starts from the closest one instead of generating the mines again
+    snapshots: Vec<Snapshot>,
+    speed: u32,
+    paused: bool,
+    /// The timeline is being dragged
+    scrubbing: bool,
+    back: bool,
+}
+
+impl ReplayState {
+    /// The height of the timeline below the board
+    pub const TIMELINE_HEIGHT: f32 = 32.0;
+
+    /// How far the arrow keys move the replay
+    const STEP: Duration = Duration::from_secs(5);
+
+    /// How many actions there are between the snapshots
+    const SNAPSHOT_INTERVAL: usize = 50;
+
+    pub fn new(ctx: &mut Context, replay: Replay) -> GameResult<Self> {
+        let view = BoardView::new(
+            ctx,
+            &replay.game_config,
+            GameState::board_offset(&replay.game_config),
+        )?;
+        let hud = Hud::new(ctx, Self::screen_size(&replay.game_config).0)
+            .map_err(|e| ggez::GameError::ResourceLoadError(e.to_string()))?;
+        let font = graphics::Font::new(ctx, "/Kenney_Future.ttf")?;
+
+        let board = replay.new_board();
+        let mut state = ReplayState {
+            snapshots: vec![Snapshot {
+                next_action: 0,
+                board: board.clone(),
+                history: History::new(),
+            }],
+            board,
+            history: History::new(),
+            replay,
+            view,
+            hud,
+            font,
+            position: Duration::default(),
+            next_action: 0,
+            speed: 1,
+            paused: false,
+            scrubbing: false,
+            back: false,
+        };
+        state.play_actions();
+
+        Ok(state)
+    }
+
+    /// The size of the screen needed to fit the hud, the board and the timeline
+    pub fn screen_size(game_config: &GameConfig) -> (f32, f32) {
+        let (width, height) = GameState::screen_size(game_config);
+        (width, height + Self::TIMELINE_HEIGHT)
+    }
+
+    /// Play the actions made up to the current position
+    fn play_actions(&mut self) {
+        while let Some(timed) = self.replay.actions.get(self.next_action) {
+            if timed.time > self.position {
+                break;
+            }
+
+            let generated = self.board.is_generated();
+            self.replay
+                .apply(timed.action, &mut self.board, &mut self.history);
+            self.next_action += 1;
+
+            if (!generated && self.board.is_generated())
+                || self.next_action.is_multiple_of(Self::SNAPSHOT_INTERVAL)
+            {
+                self.snapshot();
+            }
+        }
+    }
+
+    /// Remember the board at the current action unless a later one is already remembered
+    fn snapshot(&mut self) {
+        let newest = self.snapshots.last().map_or(0, |s| s.next_action);
+        if self.next_action > newest {
+            self.snapshots.push(Snapshot {
+                next_action: self.next_action,
+                board: self.board.clone(),
+                history: self.history.clone(),
+            });
+        }
+    }
+
+    /// Move the replay to `position`.
+    /// Moving backwards plays the game again from the closest snapshot before `position`
+    fn seek(&mut self, position: Duration) {
+        let position = position.min(self.replay.duration());
+
+        if position < self.position {
+            let actions = &self.replay.actions;
+            let snapshot = self.snapshots.iter().rev().find(|snapshot| {
+                snapshot.next_action == 0 || actions[snapshot.next_action - 1].time <= position
+            });
+
+            if let Some(snapshot) = snapshot {
+                self.board = snapshot.board.clone();
+                self.history = snapshot.history.clone();
+                self.next_action = snapshot.next_action;
+            }
+        }
+
+        self.position = position;
+        self.play_actions();
+    }
+
+    /// The rectangle of the bar that shows how far into the game the replay is
+    fn timeline_rect(&self) -> graphics::Rect {
+        let (width, height) = Self::screen_size(&self.replay.game_config);
+        graphics::Rect::new(
+            48.0,
+            height - Self::TIMELINE_HEIGHT + 8.0,
+            width - 56.0,
+            Self::TIMELINE_HEIGHT - 16.0,
+        )
+    }
+
+    /// Move to the point in the game under `x` on the timeline
+    fn seek_to_x(&mut self, x: f32) {
+        let rect = self.timeline_rect();
+        let fraction = ((x - rect.x) / rect.w).clamp(0.0, 1.0);
+        self.seek(self.replay.duration().mul_f32(fraction));
+    }
+
+    /// Draw the speed or pause sign and the timeline with the played part filled
+    fn draw_timeline(&self, ctx: &mut Context) -> GameResult {
+        let rect = self.timeline_rect();
+        let colors = &self.replay.game_config.colors;
+
+        let label = if self.paused {
+            "||".to_string()
+        } else {
+            format!("{}x", self.speed)
+        };
+        let mut text = graphics::Text::new(label);
+        text.set_font(self.font, graphics::Scale::uniform(16.0));
+        let position = cgmath::point2(8.0, rect.y + (rect.h - text.height(ctx) as f32) / 2.0);
+        graphics::draw(ctx, &text, graphics::DrawParam::default().dest(position))?;
+
+        let background = graphics::Mesh::new_rectangle(
+            ctx,
+            graphics::DrawMode::fill(),
+            rect,
+            colors.square.into(),
+        )?;
+        graphics::draw(ctx, &background, graphics::DrawParam::default())?;
+
+        let duration = self.replay.duration().as_secs_f32();
+        if duration > 0.0 {
+            let played = graphics::Rect::new(
+                rect.x,
+                rect.y,
+                rect.w * self.position.as_secs_f32() / duration,
+                rect.h,
+            );
+            if played.w > 0.0 {
+                let mesh = graphics::Mesh::new_rectangle(
+                    ctx,
+                    graphics::DrawMode::fill(),
+                    played,
+                    colors.selected_square.into(),
+                )?;
+                graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl State for ReplayState {
+    fn update(&mut self, ctx: &mut Context) -> Result<UpdateResult, LocatedError> {
+        if self.back {
+            set_screen_size(ctx, UiState::MENU_SIZE)?;
+            return Ok(UpdateResult::Pop);
+        }
+
+        if !self.paused && !self.scrubbing {
+            let position = self.position + ggez::timer::delta(ctx) * self.speed;
+            self.seek(position);
+
+            // Stop at the end so space can play it again from the start
+            if self.position >= self.replay.duration() {
+                self.paused = true;
+            }
+        }
+
+        Ok(UpdateResult::Block)
+    }
+
+    /// Draw the hud with the time of the replay, the board and the timeline
+    fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        self.hud
+            .draw(ctx, &self.board, self.position)
+            .map_err(|e| ggez::GameError::RenderError(e.to_string()))?;
+        self.view.draw(ctx, &self.board, &[])?;
+        self.draw_timeline(ctx)?;
+        Ok(())
+    }
+
+    /// Pressing on the timeline starts scrubbing
+    fn mouse_button_down_event(
+        &mut self,
+        _ctx: &mut Context,
+        button: MouseButton,
+        x: f32,
+        y: f32,
+    ) -> GameResult<EventResult> {
+        let mut rect = self.timeline_rect();
+        // Make the thin bar easier to hit
+        rect.y -= 8.0;
+        rect.h += 16.0;
+
+        if button == MouseButton::Left && rect.contains(cgmath::point2(x, y)) {
+            self.scrubbing = true;
+            self.seek_to_x(x);
+        }
+
+        Ok(EventResult::Block)
+    }
+
+    fn mouse_button_up_event(
+        &mut self,
+        _ctx: &mut Context,
+        button: MouseButton,
+        _x: f32,
+        _y: f32,
+    ) -> GameResult<EventResult> {
+        if button == MouseButton::Left {
+            self.scrubbing = false;
+        }
+
+        Ok(EventResult::Block)
+    }
+
+    fn mouse_motion_event(
+        &mut self,
+        _ctx: &mut Context,
+        x: f32,
+        _y: f32,
+        _dx: f32,
+        _dy: f32,
+    ) -> GameResult<EventResult> {
+        if self.scrubbing {
+            self.seek_to_x(x);
+        }
+
+        Ok(EventResult::Block)
+    }
+
+    fn key_up_event(
+        &mut self,
+        _ctx: &mut Context,
+        keycode: KeyCode,
+        _keymods: KeyMods,
+    ) -> GameResult<EventResult> {
+        match keycode {
+            KeyCode::Space => {
+                if self.paused && self.position >= self.replay.duration() {
+                    self.seek(Duration::default());
+                }
+                self.paused = !self.paused;
+            }
+            KeyCode::Key1 => self.speed = 1,
+            KeyCode::Key2 => self.speed = 2,
+            KeyCode::Key4 => self.speed = 4,
+            KeyCode::Left => self.seek(self.position.saturating_sub(Self::STEP)),
+            KeyCode::Right => self.seek(self.position + Self::STEP),
+            KeyCode::Home => self.seek(Duration::default()),
+            KeyCode::Back => self.back = true,
+            _ => return Ok(EventResult::LetThrough),
+        }
+
+        Ok(EventResult::Block)
+    }
+}
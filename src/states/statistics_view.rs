@@ -0,0 +1,95 @@
+use ggez::{graphics, Context, GameResult};
+
+use crate::statistics::Statistics;
+
+/// # Statistics View
+/// Shows the statistics of one board configuration at a time in the statistics screen
+pub struct StatisticsView {
+    statistics: Statistics,
+    selected: usize,
+    font: graphics::Font,
+}
+
+impl StatisticsView {
+    /// The y position of the first line
+    const TOP: f32 = 80.0;
+    /// The x position of the lines
+    const LEFT: f32 = 32.0;
+
+    pub fn new(statistics: Statistics, font: graphics::Font) -> Self {
+        StatisticsView {
+            statistics,
+            selected: 0,
+            font,
+        }
+    }
+
+    /// Show the board `offset` steps away, wrapping around at the ends
+    pub fn select(&mut self, offset: i64) {
+        let count = self.statistics.boards.len() as i64;
+        if count > 0 {
+            self.selected = (self.selected as i64 + offset).rem_euclid(count) as usize;
+        }
+    }
+
+    /// The lines of text shown for the selected board with their size
+    fn lines(&self) -> Vec<(String, f32)> {
+        let board = match self.statistics.boards.get(self.selected) {
+            Some(board) => board,
+            None => return vec![("No games played yet".to_string(), 16.0)],
+        };
+
+        let mut lines = vec![
+            (board.key.name().to_string(), 24.0),
+            (board.key.description(), 12.0),
+            (format!("Played: {}", board.played), 16.0),
+            (
+                format!("Won: {} ({:.0}%)", board.won, board.win_rate()),
+                16.0,
+            ),
+            (
+                format!(
+                    "Streak: {} Best: {}",
+                    board.current_streak, board.best_streak
+                ),
+                16.0,
+            ),
+            (
+                format!("Best 3BV/s: {:.2}", board.best_three_bv_per_second),
+                16.0,
+            ),
+            ("Best times".to_string(), 20.0),
+        ];
+
+        for (i, score) in board.best_times.iter().enumerate() {
+            let name = if score.name.is_empty() {
+                "-"
+            } else {
+                &score.name
+            };
+            lines.push((
+                format!("{}. {} {:.1}s", i + 1, name, score.time.as_secs_f32()),
+                16.0,
+            ));
+        }
+
+        lines
+    }
+
+    pub fn draw(&self, ctx: &mut Context) -> GameResult {
+        let mut y = Self::TOP;
+
+        for (line, size) in self.lines() {
+            let mut text = graphics::Text::new(line);
+            text.set_font(self.font, graphics::Scale::uniform(size));
+            graphics::draw(
+                ctx,
+                &text,
+                graphics::DrawParam::default().dest(cgmath::point2(Self::LEFT, y)),
+            )?;
+            y += size + 8.0;
+        }
+
+        Ok(())
+    }
+}
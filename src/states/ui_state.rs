@@ -1,106 +1,740 @@
+use std::collections::HashSet;
+
 use crate::{
+    board::Layout,
+    config::{Action, Difficulty, GameConfig},
     err_here,
     error::LocatedError,
-    sprite_sheet::{SheetInfo, SpriteSheet},
-    state::{EventResult, State, UpdateResult},
-    ui::{Element, Panel},
+    replay::Replay,
+    save_game::SaveGame,
+    screen::set_screen_size,
+    sprite_sheet::SpriteSheet,
+    state::{EventResult, Message, State, UpdateResult},
+    statistics::Statistics,
+    ui::{Button, Element, Panel, SMALL_BUTTON_SPRITES},
+};
+use ggez::{
+    graphics,
+    input::keyboard::{self, KeyCode, KeyMods},
+    Context,
+};
+use log::error;
+
+use super::{
+    bindings_editor::BindingsEditor,
+    custom_game::{CustomField, CustomGame},
+    name_entry::NameEntry,
+    statistics_view::StatisticsView,
+    GameState, GameSummary, ReplayState,
 };
-use ggez::{filesystem, graphics, Context};
-use ron::de::from_reader;
+
+/// What happens when an entry in a menu is clicked
+#[derive(Clone, Debug)]
+pub enum MenuAction {
+    /// Continue the saved game
+    Continue,
+    /// Start a game with the config
+    StartGame(GameConfig),
+    /// Start a game on a board loaded from a file, the config has the size and mines of the layout
+    StartLayout(GameConfig, Layout),
+    /// Open the custom game dialog starting from the config
+    OpenCustomGame(GameConfig),
+    /// Change a value in the custom game dialog, holding shift changes it ten times as much
+    Adjust(CustomField, i64),
+    /// Start a game with the config in the custom game dialog
+    StartCustomGame,
+    /// Watch the replay of the last finished game
+    WatchReplay,
+    /// Open the statistics screen
+    OpenStatistics,
+    /// Show the statistics of the board that many steps away
+    SelectStatistics(i64),
+    /// Save the name entered for a new best time
+    SaveName,
+    /// Write the mines of the finished game to a file
+    ExportBoard,
+    /// Open the screen where the controls are changed
+    OpenControls,
+    /// Wait for a key or mouse button to bind to the action
+    Rebind(Action),
+    /// Write the changed controls to the config
+    SaveControls,
+    /// Close the game over overlay and tell the game what to do next
+    GameOver(Message),
+    /// Close this state and go back to the one below
+    Back,
+}
 
 pub struct UiState {
     sprite_sheet: SpriteSheet,
     panel: Panel,
     elements: Vec<Element>,
+    buttons: Vec<Button<MenuAction>>,
+    /// The action of the last clicked button, handled in the next update
+    action: Option<MenuAction>,
+    custom_game: Option<CustomGame>,
+    statistics: Option<StatisticsView>,
+    name_entry: Option<NameEntry>,
+    bindings_editor: Option<BindingsEditor>,
+    /// The finished game the game over overlay is shown for
+    game_summary: Option<GameSummary>,
+    /// The config the main menu starts games from
+    game_config: Option<GameConfig>,
+    /// Make the main menu again in the next update, set when a state is pushed on top of it
+    refresh: bool,
+    /// Close this state on any key
+    close_on_key: bool,
+    /// Keys pressed down while this state closes on any key.
+    /// Releasing a key that was already down when it was shown, like the one that ended the game, keeps it open
+    keys_down: HashSet<KeyCode>,
     is_menu: bool,
     quit: bool,
 }
 
 impl UiState {
-    pub fn create_main_menu_state(ctx: &mut Context) -> Result<Self, LocatedError> {
-        let sprite_sheet = {
-            let file =
-                filesystem::open(ctx, "/Ui/Spritesheet/colored_sheet.ron").map_err(err_here!())?;
-            let sheet_info: SheetInfo = from_reader(file).map_err(err_here!())?;
-            sheet_info.into_sprite_sheet(ctx, "blue")?
-        };
-
-        let panel_sprites = [
-            "panel_top_left".to_string(),
-            "panel_top".to_string(),
-            "panel_top_right".to_string(),
-            "panel_left".to_string(),
-            "panel_center".to_string(),
-            "panel_right".to_string(),
-            "panel_bottom_left".to_string(),
-            "panel_bottom".to_string(),
-            "panel_bottom_right".to_string(),
-        ];
+    /// The size of the screen while the main menu is shown
+    pub const MENU_SIZE: (f32, f32) = (320.0, 616.0);
 
-        let panel_rect = graphics::Rect::new_i32(32, 32, 244, 244);
+    /// The room the export button takes in the game over overlay
+    const EXPORT_ROW_HEIGHT: f32 = 57.0;
 
-        let panel = Panel::new(panel_rect, panel_sprites);
-
-        Ok(UiState {
+    /// A menu with the panel, elements and buttons
+    fn new(
+        sprite_sheet: SpriteSheet,
+        panel: Panel,
+        elements: Vec<Element>,
+        buttons: Vec<Button<MenuAction>>,
+    ) -> Self {
+        UiState {
             sprite_sheet,
-            elements: vec![],
-            is_menu: true,
             panel,
+            elements,
+            buttons,
+            action: None,
+            custom_game: None,
+            statistics: None,
+            name_entry: None,
+            bindings_editor: None,
+            game_summary: None,
+            game_config: None,
+            refresh: false,
+            close_on_key: false,
+            keys_down: HashSet::new(),
+            is_menu: true,
             quit: false,
+        }
+    }
+
+    /// Create the main menu where a difficulty is picked or a saved game is continued.
+    /// Custom opens a dialog starting from the board size and mines of the given config
+    pub fn create_main_menu_state(
+        ctx: &mut Context,
+        game_config: GameConfig,
+    ) -> Result<Self, LocatedError> {
+        let sprite_sheet = SpriteSheet::load(ctx, "/Ui/Spritesheet/colored_sheet.ron", "blue")?;
+
+        let panel_rect = graphics::Rect::new(
+            16.0,
+            16.0,
+            Self::MENU_SIZE.0 - 32.0,
+            Self::MENU_SIZE.1 - 32.0,
+        );
+
+        let panel = Panel::with_default_sprites(panel_rect);
+
+        let font = graphics::Font::new(ctx, "/Kenney_Future.ttf").map_err(err_here!())?;
+
+        let title = Element::new_element(cgmath::point2(48.0, 32.0)).set_label(
+            "Mine Sweeper",
+            &font,
+            &graphics::Scale::uniform(32.0),
+        );
+
+        let mut menu_entries = vec![("Continue", MenuAction::Continue)];
+        menu_entries.extend(
+            Difficulty::ALL
+                .iter()
+                .map(|d| (d.name(), MenuAction::StartGame(d.apply(&game_config)))),
+        );
+        menu_entries.push(("Custom", MenuAction::OpenCustomGame(game_config.clone())));
+        menu_entries.push(("Replay", MenuAction::WatchReplay));
+        menu_entries.push(("Statistics", MenuAction::OpenStatistics));
+        menu_entries.push(("Controls", MenuAction::OpenControls));
+
+        let button_size = sprite_sheet
+            .get_sprite_pixel_size("button00")
+            .unwrap_or_else(|| cgmath::vec2(190.0, 49.0));
+
+        let mut buttons: Vec<Button<MenuAction>> = menu_entries
+            .into_iter()
+            .enumerate()
+            .map(|(i, (name, action))| {
+                let position = cgmath::point2(
+                    (Self::MENU_SIZE.0 - button_size.x) / 2.0,
+                    80.0 + i as f32 * (button_size.y + 8.0),
+                );
+
+                Button::new(position, action).set_label(
+                    name,
+                    &font,
+                    &graphics::Scale::uniform(24.0),
+                )
+            })
+            .collect();
+
+        // Continue is only available if there is a saved game and replay if a game has been finished
+        let has_save = SaveGame::exists(ctx);
+        let has_replay = Replay::exists(ctx);
+        for button in &mut buttons {
+            match button.message() {
+                MenuAction::Continue => button.set_enabled(has_save),
+                MenuAction::WatchReplay => button.set_enabled(has_replay),
+                _ => {}
+            }
+        }
+
+        Ok(UiState {
+            game_config: Some(game_config),
+            ..Self::new(sprite_sheet, panel, vec![title], buttons)
         })
     }
 
-    pub fn create_game_over_state(ctx: &mut Context, has_won: bool) -> Result<Self, LocatedError> {
-        let sprite_sheet = {
-            let file =
-                filesystem::open(ctx, "/Ui/Spritesheet/colored_sheet.ron").map_err(err_here!())?;
-            let sheet_info: SheetInfo = from_reader(file).map_err(err_here!())?;
-            sheet_info.into_sprite_sheet(ctx, "blue")?
+    /// Create the screen showing the statistics and best times of every board that has been played
+    pub fn create_statistics_state(ctx: &mut Context) -> Result<Self, LocatedError> {
+        let sprite_sheet = SpriteSheet::load(ctx, "/Ui/Spritesheet/colored_sheet.ron", "blue")?;
+
+        let panel_rect = graphics::Rect::new(
+            16.0,
+            16.0,
+            Self::MENU_SIZE.0 - 32.0,
+            Self::MENU_SIZE.1 - 32.0,
+        );
+
+        let panel = Panel::with_default_sprites(panel_rect);
+
+        let font = graphics::Font::new(ctx, "/Kenney_Future.ttf").map_err(err_here!())?;
+        let label_scale = graphics::Scale::uniform(24.0);
+
+        let title = Element::new_element(cgmath::point2(88.0, 32.0)).set_label(
+            "Statistics",
+            &font,
+            &graphics::Scale::uniform(32.0),
+        );
+
+        let statistics = Statistics::load(ctx)?;
+        let several_boards = statistics.boards.len() > 1;
+
+        let mut buttons = vec![Button::new(cgmath::point2(24.0, 24.0), MenuAction::Back)
+            .set_sprites(SMALL_BUTTON_SPRITES)
+            .set_label("<", &font, &label_scale)];
+
+        // Buttons to page through the boards
+        let bottom = Self::MENU_SIZE.1 - 88.0;
+        for (x, label, offset) in [(32.0, "-", -1), (239.0, "+", 1)].iter() {
+            let mut button = Button::new(
+                cgmath::point2(*x, bottom),
+                MenuAction::SelectStatistics(*offset),
+            )
+            .set_sprites(SMALL_BUTTON_SPRITES)
+            .set_label(label, &font, &label_scale);
+            button.set_enabled(several_boards);
+            buttons.push(button);
+        }
+
+        Ok(UiState {
+            statistics: Some(StatisticsView::new(statistics, font)),
+            ..Self::new(sprite_sheet, panel, vec![title], buttons)
+        })
+    }
+
+    /// Create the screen where the key and mouse button of every action is changed.
+    /// The bindings are written to the config in the user config directory when they are saved
+    pub fn create_controls_state(ctx: &mut Context) -> Result<Self, LocatedError> {
+        let sprite_sheet = SpriteSheet::load(ctx, "/Ui/Spritesheet/colored_sheet.ron", "blue")?;
+
+        let panel_rect = graphics::Rect::new(
+            16.0,
+            16.0,
+            Self::MENU_SIZE.0 - 32.0,
+            Self::MENU_SIZE.1 - 32.0,
+        );
+
+        let panel = Panel::with_default_sprites(panel_rect);
+
+        let font = graphics::Font::new(ctx, "/Kenney_Future.ttf").map_err(err_here!())?;
+        let label_scale = graphics::Scale::uniform(24.0);
+
+        let title = Element::new_element(cgmath::point2(96.0, 32.0)).set_label(
+            "Controls",
+            &font,
+            &graphics::Scale::uniform(32.0),
+        );
+
+        let mut buttons = vec![Button::new(cgmath::point2(24.0, 24.0), MenuAction::Back)
+            .set_sprites(SMALL_BUTTON_SPRITES)
+            .set_label("<", &font, &label_scale)];
+
+        for action in Action::ALL.iter() {
+            buttons.push(
+                Button::new(
+                    cgmath::point2(239.0, BindingsEditor::row_y(*action)),
+                    MenuAction::Rebind(*action),
+                )
+                .set_sprites(SMALL_BUTTON_SPRITES)
+                .set_label("Set", &font, &graphics::Scale::uniform(16.0)),
+            );
+        }
+
+        // Saving is next to the title since the actions fill the screen
+        buttons.push(
+            Button::new(
+                cgmath::point2(Self::MENU_SIZE.0 - 73.0, 24.0),
+                MenuAction::SaveControls,
+            )
+            .set_sprites(SMALL_BUTTON_SPRITES)
+            .set_label("Save", &font, &graphics::Scale::uniform(12.0)),
+        );
+
+        let game_config = GameConfig::load(ctx)?;
+
+        Ok(UiState {
+            bindings_editor: Some(BindingsEditor::new(game_config, font)),
+            ..Self::new(sprite_sheet, panel, vec![title], buttons)
+        })
+    }
+
+    /// Create the dialog where the size and mines of a custom game are picked.
+    /// Invalid values are shown as an error and the game can not be started until they are fixed
+    pub fn create_custom_game_state(
+        ctx: &mut Context,
+        game_config: GameConfig,
+    ) -> Result<Self, LocatedError> {
+        let sprite_sheet = SpriteSheet::load(ctx, "/Ui/Spritesheet/colored_sheet.ron", "blue")?;
+
+        let panel_rect = graphics::Rect::new(
+            16.0,
+            16.0,
+            Self::MENU_SIZE.0 - 32.0,
+            Self::MENU_SIZE.1 - 32.0,
+        );
+
+        let panel = Panel::with_default_sprites(panel_rect);
+
+        let font = graphics::Font::new(ctx, "/Kenney_Future.ttf").map_err(err_here!())?;
+        let label_scale = graphics::Scale::uniform(24.0);
+
+        let mut elements = vec![Element::new_element(cgmath::point2(104.0, 32.0)).set_label(
+            "Custom",
+            &font,
+            &graphics::Scale::uniform(32.0),
+        )];
+
+        let mut buttons = vec![Button::new(cgmath::point2(24.0, 24.0), MenuAction::Back)
+            .set_sprites(SMALL_BUTTON_SPRITES)
+            .set_label("<", &font, &label_scale)];
+
+        for field in CustomField::ALL.iter() {
+            let y = field.row_y();
+
+            elements.push(
+                Element::new_element(cgmath::point2(32.0, y + 12.0)).set_label(
+                    field.name(),
+                    &font,
+                    &graphics::Scale::uniform(20.0),
+                ),
+            );
+
+            buttons.push(
+                Button::new(cgmath::point2(128.0, y), MenuAction::Adjust(*field, -1))
+                    .set_sprites(SMALL_BUTTON_SPRITES)
+                    .set_label("-", &font, &label_scale),
+            );
+            buttons.push(
+                Button::new(cgmath::point2(239.0, y), MenuAction::Adjust(*field, 1))
+                    .set_sprites(SMALL_BUTTON_SPRITES)
+                    .set_label("+", &font, &label_scale),
+            );
+        }
+
+        buttons.push(
+            Button::new(cgmath::point2(65.0, 304.0), MenuAction::StartCustomGame).set_label(
+                "Start",
+                &font,
+                &label_scale,
+            ),
+        );
+
+        let mut state = UiState {
+            custom_game: Some(CustomGame::new(game_config, font)),
+            ..Self::new(sprite_sheet, panel, elements, buttons)
         };
+        state.update_start_button();
 
-        let panel_sprites = [
-            "panel_top_left".to_string(),
-            "panel_top".to_string(),
-            "panel_top_right".to_string(),
-            "panel_left".to_string(),
-            "panel_center".to_string(),
-            "panel_right".to_string(),
-            "panel_bottom_left".to_string(),
-            "panel_bottom".to_string(),
-            "panel_bottom_right".to_string(),
-        ];
+        Ok(state)
+    }
+
+    /// Returns true while the controls screen waits for a key or mouse button
+    fn is_rebinding(&self) -> bool {
+        self.bindings_editor
+            .as_ref()
+            .is_some_and(|bindings_editor| bindings_editor.is_waiting())
+    }
+
+    /// Push the state on top of this one.
+    /// The main menu is made again when it is shown after it so it has the saved game,
+    /// the last replay and the controls changed in the meantime
+    fn push(&mut self, state: impl State + 'static) -> UpdateResult {
+        self.refresh = true;
+        UpdateResult::Push(Box::new(state))
+    }
 
-        let panel_rect = graphics::Rect::new_i32(32, 320 / 2 - 32, 320 - 64, 64);
+    /// The seed of the config is only used for the first game started from the menu
+    fn forget_seed(&mut self) {
+        if let Some(game_config) = &mut self.game_config {
+            game_config.seed = None;
+        }
+    }
 
-        let panel = Panel::new(panel_rect, panel_sprites);
+    /// Start a game on the layout as soon as the menu is updated, used for boards given on the command line.
+    /// The size and the amount of mines in the config have to be the ones of the layout
+    pub fn start_layout(mut self, game_config: GameConfig, layout: Layout) -> Self {
+        self.action = Some(MenuAction::StartLayout(game_config, layout));
+        self
+    }
+
+    /// Only let the custom game be started when the values are valid
+    fn update_start_button(&mut self) {
+        if let Some(custom_game) = &self.custom_game {
+            let valid = custom_game.validate().is_ok();
+            for button in &mut self.buttons {
+                if let MenuAction::StartCustomGame = button.message() {
+                    button.set_enabled(valid);
+                }
+            }
+        }
+    }
+
+    /// Create the overlay shown when a game is over together with the seed the game was played with
+    /// and how efficiently it was played.
+    /// A new best time asks for a name before the overlay can be closed
+    pub fn create_game_over_state(
+        ctx: &mut Context,
+        summary: &GameSummary,
+    ) -> Result<Self, LocatedError> {
+        let sprite_sheet = SpriteSheet::load(ctx, "/Ui/Spritesheet/colored_sheet.ron", "blue")?;
+
+        let mut height = if summary.new_best.is_some() {
+            309.0
+        } else {
+            201.0
+        };
+        // Loaded boards already have a file so they can not be exported
+        if summary.layout.is_some() {
+            height += Self::EXPORT_ROW_HEIGHT;
+        }
+
+        // Center the panel on the screen since the size of the screen depends on the board
+        let screen = graphics::screen_coordinates(ctx);
+        let origin = cgmath::point2(
+            screen.x + (screen.w - 256.0) / 2.0,
+            screen.y + (screen.h - height) / 2.0,
+        );
+
+        let panel_rect = graphics::Rect::new(origin.x, origin.y, 256.0, height);
+
+        let panel = Panel::with_default_sprites(panel_rect);
 
         let font = graphics::Font::new(ctx, "/Kenney_Future.ttf").map_err(err_here!())?;
         let scale = graphics::Scale::uniform(32.0);
 
-        let game_over_text = if has_won {
-            Element::new_element(cgmath::point2(85.0, 320.0 / 2.0 - 16.0))
+        let game_over_text = if summary.has_won {
+            Element::new_element(origin + cgmath::vec2(53.0, 16.0))
                 .set_label("You Won", &font, &scale)
         } else {
-            Element::new_element(cgmath::point2(64.0, 320.0 / 2.0 - 16.0))
+            Element::new_element(origin + cgmath::vec2(32.0, 16.0))
                 .set_label("You Lost", &font, &scale)
         };
 
+        // The seed of a loaded board is not what placed the mines
+        let seed = if summary.loaded {
+            "Loaded board".to_string()
+        } else {
+            format!("Seed: {}", summary.seed)
+        };
+        let seed_text = Element::new_element(origin + cgmath::vec2(16.0, 52.0)).set_label(
+            &seed,
+            &font,
+            &graphics::Scale::uniform(16.0),
+        );
+
+        let mut elements = vec![game_over_text, seed_text];
+
+        // The metrics are shown in two columns
+        let metrics = &summary.metrics;
+        let clicks = &metrics.clicks;
+        let metric_rows = [
+            (
+                format!("3BV: {}/{}", metrics.solved_three_bv, metrics.three_bv),
+                format!("3BV/s: {:.2}", metrics.three_bv_per_second()),
+            ),
+            (
+                format!("Clicks: {}", clicks.total()),
+                format!("Eff: {:.0}%", metrics.efficiency()),
+            ),
+            (
+                format!("L{} R{} C{}", clicks.left, clicks.right, clicks.chord),
+                format!("IOE: {:.2}", metrics.ioe()),
+            ),
+        ];
+        let metric_scale = graphics::Scale::uniform(12.0);
+        for (row, (left, right)) in metric_rows.iter().enumerate() {
+            let y = 76.0 + row as f32 * 16.0;
+            elements.push(
+                Element::new_element(origin + cgmath::vec2(16.0, y)).set_label(
+                    left,
+                    &font,
+                    &metric_scale,
+                ),
+            );
+            elements.push(
+                Element::new_element(origin + cgmath::vec2(136.0, y)).set_label(
+                    right,
+                    &font,
+                    &metric_scale,
+                ),
+            );
+        }
+
+        let not_counted = match (summary.hints, summary.used_undo) {
+            _ if summary.loaded => Some("Loaded boards are not counted".to_string()),
+            _ if summary.used_analysis => Some("Analysis used, not counted".to_string()),
+            (0, false) => None,
+            (0, true) => Some("Undo used, not counted".to_string()),
+            (1, _) => Some("1 hint used, not counted".to_string()),
+            (hints, _) => Some(format!("{} hints used, not counted", hints)),
+        };
+        if let Some(not_counted) = not_counted {
+            elements.push(
+                Element::new_element(origin + cgmath::vec2(16.0, 124.0)).set_label(
+                    &not_counted,
+                    &font,
+                    &graphics::Scale::uniform(12.0),
+                ),
+            );
+        }
+
+        let mut buttons = vec![];
+        let mut name_entry = None;
+        if let Some((key, date)) = &summary.new_best {
+            elements.push(
+                Element::new_element(origin + cgmath::vec2(16.0, 140.0)).set_label(
+                    "New best time!",
+                    &font,
+                    &graphics::Scale::uniform(16.0),
+                ),
+            );
+
+            let last_name = Statistics::load(ctx)?.last_name;
+            name_entry = Some(NameEntry::new(
+                key.clone(),
+                *date,
+                last_name,
+                font,
+                origin + cgmath::vec2(16.0, 164.0),
+            ));
+
+            buttons.push(
+                Button::new(origin + cgmath::vec2(33.0, 192.0), MenuAction::SaveName).set_label(
+                    "Save",
+                    &font,
+                    &graphics::Scale::uniform(24.0),
+                ),
+            );
+        }
+
+        if summary.layout.is_some() {
+            buttons.push(
+                Button::new(
+                    origin + cgmath::vec2(33.0, height - 65.0 - Self::EXPORT_ROW_HEIGHT),
+                    MenuAction::ExportBoard,
+                )
+                .set_label("Export board", &font, &graphics::Scale::uniform(20.0)),
+            );
+        }
+
+        // What to do next, the labels are short to fit the small buttons
+        let next = [
+            ("Again", Message::PlayAgain),
+            ("Same", Message::ReplayBoard),
+            ("Menu", Message::ChangeDifficulty),
+            ("Quit", Message::Quit),
+        ];
+        for (i, (label, message)) in next.iter().enumerate() {
+            buttons.push(
+                Button::new(
+                    origin + cgmath::vec2(16.0 + i as f32 * 58.0, height - 65.0),
+                    MenuAction::GameOver(*message),
+                )
+                .set_sprites(SMALL_BUTTON_SPRITES)
+                .set_label(label, &font, &graphics::Scale::uniform(11.0)),
+            );
+        }
+
         Ok(UiState {
-            sprite_sheet,
-            elements: vec![game_over_text],
-            is_menu: true,
-            panel,
-            quit: false,
+            close_on_key: name_entry.is_none(),
+            name_entry,
+            game_summary: Some(summary.clone()),
+            ..Self::new(sprite_sheet, panel, elements, buttons)
         })
     }
 }
 
 impl State for UiState {
-    fn update(&mut self, _ctx: &mut ggez::Context) -> Result<UpdateResult, LocatedError> {
+    fn update(&mut self, ctx: &mut ggez::Context) -> Result<UpdateResult, LocatedError> {
         if self.quit {
             return Ok(UpdateResult::Pop);
         }
 
+        if self.refresh {
+            if let Some(mut game_config) = self.game_config.take() {
+                game_config.bindings = GameConfig::load(ctx)?.bindings;
+                *self = Self::create_main_menu_state(ctx, game_config)?;
+            }
+        }
+
+        if let Some(action) = self.action.take() {
+            match action {
+                MenuAction::Continue => {
+                    for button in &mut self.buttons {
+                        if let MenuAction::Continue = button.message() {
+                            button.set_enabled(false);
+                        }
+                    }
+
+                    if let Some(mut save) = SaveGame::take(ctx)? {
+                        // The game is played with the current controls
+                        if let Some(game_config) = &self.game_config {
+                            save.game_config.bindings = game_config.bindings.clone();
+                        }
+
+                        set_screen_size(ctx, GameState::screen_size(&save.game_config))?;
+                        let new_state = GameState::from_save(ctx, save).map_err(err_here!())?;
+                        return Ok(self.push(new_state));
+                    }
+                }
+                MenuAction::StartGame(game_config) => {
+                    self.forget_seed();
+                    set_screen_size(ctx, GameState::screen_size(&game_config))?;
+                    let new_state = GameState::new(ctx, game_config).map_err(err_here!())?;
+                    return Ok(self.push(new_state));
+                }
+                MenuAction::StartLayout(game_config, layout) => {
+                    self.forget_seed();
+                    set_screen_size(ctx, GameState::screen_size(&game_config))?;
+                    let new_state =
+                        GameState::with_layout(ctx, game_config, layout).map_err(err_here!())?;
+                    return Ok(self.push(new_state));
+                }
+                MenuAction::OpenCustomGame(game_config) => {
+                    self.forget_seed();
+                    let new_state = UiState::create_custom_game_state(ctx, game_config)?;
+                    return Ok(self.push(new_state));
+                }
+                MenuAction::Adjust(field, amount) => {
+                    let amount = if keyboard::active_mods(ctx).contains(KeyMods::SHIFT) {
+                        amount * 10
+                    } else {
+                        amount
+                    };
+
+                    if let Some(custom_game) = &mut self.custom_game {
+                        custom_game.adjust(field, amount);
+                    }
+                    self.update_start_button();
+                }
+                MenuAction::StartCustomGame => {
+                    if let Some(custom_game) = &self.custom_game {
+                        if custom_game.validate().is_ok() {
+                            let game_config = custom_game.game_config().clone();
+                            set_screen_size(ctx, GameState::screen_size(&game_config))?;
+                            let new_state =
+                                GameState::new(ctx, game_config).map_err(err_here!())?;
+                            // The dialog is replaced so the game returns to the main menu
+                            return Ok(UpdateResult::Swap(Box::new(new_state)));
+                        }
+                    }
+                }
+                MenuAction::WatchReplay => {
+                    if let Some(replay) = Replay::load_latest(ctx)? {
+                        set_screen_size(ctx, ReplayState::screen_size(&replay.game_config))?;
+                        let new_state = ReplayState::new(ctx, replay).map_err(err_here!())?;
+                        return Ok(self.push(new_state));
+                    }
+                }
+                MenuAction::OpenStatistics => {
+                    let new_state = UiState::create_statistics_state(ctx)?;
+                    return Ok(self.push(new_state));
+                }
+                MenuAction::SelectStatistics(offset) => {
+                    if let Some(statistics) = &mut self.statistics {
+                        statistics.select(offset);
+                    }
+                }
+                MenuAction::SaveName => {
+                    if let Some(name_entry) = self.name_entry.take() {
+                        name_entry.save(ctx)?;
+                        self.elements.push(name_entry.into_label());
+                    }
+                    self.buttons
+                        .retain(|button| !matches!(button.message(), MenuAction::SaveName));
+                    // The enter key that saved the name is already down and does not close it
+                    self.close_on_key = true;
+                    self.keys_down.clear();
+                }
+                MenuAction::ExportBoard => {
+                    let exported = match &self.game_summary {
+                        Some(summary) => summary.export_board(ctx),
+                        None => Ok(()),
+                    };
+
+                    // A board that can not be written should not end the game
+                    match exported {
+                        Ok(()) => {
+                            for button in &mut self.buttons {
+                                if matches!(button.message(), MenuAction::ExportBoard) {
+                                    button.set_enabled(false);
+                                }
+                            }
+                        }
+                        Err(e) => error!("Could not export the board: {}", e),
+                    }
+                }
+                MenuAction::GameOver(message) => {
+                    // A name that has not been saved yet is saved with the choice
+                    if let Some(name_entry) = &self.name_entry {
+                        name_entry.save(ctx)?;
+                    }
+                    return Ok(UpdateResult::PopWith(message));
+                }
+                MenuAction::OpenControls => {
+                    let new_state = UiState::create_controls_state(ctx)?;
+                    return Ok(self.push(new_state));
+                }
+                MenuAction::Rebind(action) => {
+                    if let Some(bindings_editor) = &mut self.bindings_editor {
+                        bindings_editor.start_rebinding(action);
+                    }
+                }
+                MenuAction::SaveControls => {
+                    if let Some(bindings_editor) = &self.bindings_editor {
+                        bindings_editor.save(ctx)?;
+                    }
+                    return Ok(UpdateResult::Pop);
+                }
+                MenuAction::Back => return Ok(UpdateResult::Pop),
+            }
+        }
+
         if self.is_menu {
             Ok(UpdateResult::Block)
         } else {
@@ -115,6 +749,26 @@ impl State for UiState {
             element.draw(ctx, &self.sprite_sheet)?;
         }
 
+        for button in &self.buttons {
+            button.draw(ctx, &self.sprite_sheet)?;
+        }
+
+        if let Some(custom_game) = &self.custom_game {
+            custom_game.draw(ctx)?;
+        }
+
+        if let Some(statistics) = &self.statistics {
+            statistics.draw(ctx)?;
+        }
+
+        if let Some(name_entry) = &self.name_entry {
+            name_entry.draw(ctx)?;
+        }
+
+        if let Some(bindings_editor) = &self.bindings_editor {
+            bindings_editor.draw(ctx)?;
+        }
+
         Ok(())
     }
 
@@ -125,32 +779,120 @@ impl State for UiState {
     fn mouse_button_down_event(
         &mut self,
         _ctx: &mut ggez::Context,
-        _button: ggez::input::mouse::MouseButton,
-        _x: f32,
-        _y: f32,
+        button: ggez::input::mouse::MouseButton,
+        x: f32,
+        y: f32,
     ) -> ggez::GameResult<EventResult> {
+        if self.is_rebinding() {
+            return Ok(EventResult::Block);
+        }
+
+        if button == ggez::input::mouse::MouseButton::Left {
+            for ui_button in &mut self.buttons {
+                ui_button.mouse_down(cgmath::point2(x, y), &self.sprite_sheet);
+            }
+        }
+
         Ok(EventResult::Block)
     }
 
+    /// Triggers the action of the clicked button.
+    /// While the controls screen waits for a binding the button is bound instead
     fn mouse_button_up_event(
         &mut self,
         _ctx: &mut ggez::Context,
-        _button: ggez::input::mouse::MouseButton,
-        _x: f32,
-        _y: f32,
+        button: ggez::input::mouse::MouseButton,
+        x: f32,
+        y: f32,
     ) -> ggez::GameResult<EventResult> {
-        self.quit = true;
+        if let Some(bindings_editor) = self.bindings_editor.as_mut().filter(|e| e.is_waiting()) {
+            bindings_editor.bind_mouse(button);
+        } else if button == ggez::input::mouse::MouseButton::Left {
+            for ui_button in &mut self.buttons {
+                if let Some(action) = ui_button.mouse_up(cgmath::point2(x, y), &self.sprite_sheet) {
+                    self.action = Some(action);
+                }
+            }
+        }
+
         Ok(EventResult::Block)
     }
 
     fn mouse_motion_event(
         &mut self,
         _ctx: &mut ggez::Context,
-        _x: f32,
-        _y: f32,
+        x: f32,
+        y: f32,
         _dx: f32,
         _dy: f32,
     ) -> ggez::GameResult<EventResult> {
+        for button in &mut self.buttons {
+            button.mouse_motion(cgmath::point2(x, y), &self.sprite_sheet);
+        }
+
         Ok(EventResult::Block)
     }
+
+    /// The game over overlay closes on any key pressed after it was shown to show the board below it.
+    /// The key that undoes the lost move in practice mode reaches the game first on key down.
+    /// While a name is entered backspace removes a character and enter saves it
+    fn key_up_event(
+        &mut self,
+        _ctx: &mut ggez::Context,
+        keycode: KeyCode,
+        _keymods: KeyMods,
+    ) -> ggez::GameResult<EventResult> {
+        if let Some(name_entry) = &mut self.name_entry {
+            match keycode {
+                KeyCode::Back => name_entry.pop(),
+                KeyCode::Return | KeyCode::NumpadEnter => self.action = Some(MenuAction::SaveName),
+                _ => {}
+            }
+            return Ok(EventResult::Block);
+        }
+
+        if self.close_on_key && self.keys_down.remove(&keycode) {
+            self.quit = true;
+        }
+
+        Ok(EventResult::LetThrough)
+    }
+
+    /// Keys pressed while a name is entered are kept from the game below.
+    /// Keys pressed on the game over overlay are remembered so releasing them closes it.
+    /// While the controls screen waits for a binding the key is bound
+    fn key_down_event(
+        &mut self,
+        _ctx: &mut ggez::Context,
+        keycode: KeyCode,
+        keymods: KeyMods,
+        _repeat: bool,
+    ) -> ggez::GameResult<EventResult> {
+        if let Some(bindings_editor) = self.bindings_editor.as_mut().filter(|e| e.is_waiting()) {
+            bindings_editor.bind_key(keycode, keymods);
+            Ok(EventResult::Block)
+        } else if self.name_entry.is_some() {
+            Ok(EventResult::Block)
+        } else {
+            if self.close_on_key {
+                self.keys_down.insert(keycode);
+            }
+            Ok(EventResult::LetThrough)
+        }
+    }
+
+    /// Typed characters are added to the name being entered
+    fn text_input_event(
+        &mut self,
+        _ctx: &mut ggez::Context,
+        character: char,
+    ) -> ggez::GameResult<EventResult> {
+        match &mut self.name_entry {
+            Some(name_entry) => {
+                name_entry.push(character);
+                Ok(EventResult::Block)
+            }
+            None => Ok(EventResult::LetThrough),
+        }
+    }
 }
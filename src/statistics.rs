@@ -0,0 +1,259 @@
+use std::{io::Write, time::Duration};
+
+use ggez::{filesystem, Context};
+use log::{info, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    board::{Generation, Metrics, Topology},
+    config::{Difficulty, GameConfig},
+    err_here,
+    error::LocatedError,
+};
+
+/// The board configuration statistics are kept for
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct BoardKey {
+    pub game_size: (usize, usize),
+    pub number_of_mines: usize,
+    pub generation: Generation,
+    #[serde(default)]
+    pub topology: Topology,
+}
+
+impl BoardKey {
+    pub fn new(game_config: &GameConfig) -> Self {
+        BoardKey {
+            game_size: game_config.game_size,
+            number_of_mines: game_config.number_of_mines,
+            generation: game_config.generation,
+            topology: game_config.topology,
+        }
+    }
+
+    /// The name of the difficulty with the same board or custom.
+    /// The difficulties are only for boards of squares
+    pub fn name(&self) -> &'static str {
+        Difficulty::ALL
+            .iter()
+            .find(|d| {
+                self.topology == Topology::Square
+                    && d.game_size() == self.game_size
+                    && d.number_of_mines() == self.number_of_mines
+            })
+            .map_or("Custom", |d| d.name())
+    }
+
+    /// The size, mines and generation of the board and the shape of the squares if they are not square
+    pub fn description(&self) -> String {
+        let description = format!(
+            "{}x{}, {} mines, {}",
+            self.game_size.0,
+            self.game_size.1,
+            self.number_of_mines,
+            self.generation.name()
+        );
+
+        match self.topology {
+            Topology::Square => description,
+            topology => format!("{}, {}", description, topology.name()),
+        }
+    }
+}
+
+/// A won game in the best times table
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq)]
+pub struct HighScore {
+    pub name: String,
+    pub time: Duration,
+    pub three_bv_per_second: f32,
+    /// Seconds since the unix epoch when the game started
+    pub date: u64,
+}
+
+/// A finished game that is recorded in the statistics
+#[derive(Clone, Debug)]
+pub struct GameRecord {
+    pub won: bool,
+    pub metrics: Metrics,
+    /// Seconds since the unix epoch when the game started
+    pub date: u64,
+}
+
+/// The statistics of one board configuration
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct BoardStatistics {
+    pub key: BoardKey,
+    pub played: u32,
+    pub won: u32,
+    pub current_streak: u32,
+    pub best_streak: u32,
+    pub best_three_bv_per_second: f32,
+    /// The fastest won games, fastest first
+    pub best_times: Vec<HighScore>,
+}
+
+impl BoardStatistics {
+    fn new(key: BoardKey) -> Self {
+        BoardStatistics {
+            key,
+            played: 0,
+            won: 0,
+            current_streak: 0,
+            best_streak: 0,
+            best_three_bv_per_second: 0.0,
+            best_times: vec![],
+        }
+    }
+
+    /// The share of the played games that were won in percent
+    pub fn win_rate(&self) -> f32 {
+        if self.played > 0 {
+            self.won as f32 * 100.0 / self.played as f32
+        } else {
+            0.0
+        }
+    }
+}
+
+/// # Statistics
+/// The games played on every board configuration and the best times.
+/// Kept in the user config directory
+#[derive(Serialize, Deserialize, Clone, Debug)]
+pub struct Statistics {
+    /// The version of the statistics format, files with another version are not loaded
+    pub version: u32,
+    /// The name last entered for a best time, suggested for the next one
+    pub last_name: String,
+    pub boards: Vec<BoardStatistics>,
+}
+
+impl Default for Statistics {
+    fn default() -> Self {
+        Statistics {
+            version: Self::VERSION,
+            last_name: String::new(),
+            boards: vec![],
+        }
+    }
+}
+
+impl Statistics {
+    /// The current version of the statistics format
+    pub const VERSION: u32 = 1;
+
+    /// The number of best times kept for every board
+    pub const BEST_TIMES: usize = 5;
+
+    /// The path of the statistics file in the user config directory
+    const PATH: &'static str = "/statistics.ron";
+
+    /// Load the statistics, a file that can not be read or has another version starts new statistics
+    pub fn load(ctx: &mut Context) -> Result<Self, LocatedError> {
+        if !filesystem::exists(ctx, Self::PATH) {
+            return Ok(Self::default());
+        }
+
+        let file = filesystem::open(ctx, Self::PATH).map_err(err_here!())?;
+        match ron::de::from_reader::<_, Statistics>(file) {
+            Ok(statistics) if statistics.version == Self::VERSION => Ok(statistics),
+            Ok(statistics) => {
+                warn!(
+                    "Ignoring statistics with version {}, expected {}",
+                    statistics.version,
+                    Self::VERSION
+                );
+                Ok(Self::default())
+            }
+            Err(e) => {
+                warn!("Ignoring statistics that could not be read: {:?}", e);
+                Ok(Self::default())
+            }
+        }
+    }
+
+    /// Write the statistics to the user config directory
+    pub fn save(&self, ctx: &mut Context) -> Result<(), LocatedError> {
+        let text =
+            ron::ser::to_string_pretty(self, ron::ser::PrettyConfig::new()).map_err(err_here!())?;
+        let mut file = filesystem::create(ctx, Self::PATH).map_err(err_here!())?;
+        file.write_all(text.as_bytes()).map_err(err_here!())?;
+
+        info!("Saved statistics to {}", Self::PATH);
+        Ok(())
+    }
+
+    /// The statistics of a board if a game has been played on it
+    pub fn board(&self, key: &BoardKey) -> Option<&BoardStatistics> {
+        self.boards.iter().find(|board| &board.key == key)
+    }
+
+    fn board_mut(&mut self, key: &BoardKey) -> &mut BoardStatistics {
+        let index = match self.boards.iter().position(|board| &board.key == key) {
+            Some(index) => index,
+            None => {
+                self.boards.push(BoardStatistics::new(key.clone()));
+                self.boards.len() - 1
+            }
+        };
+
+        &mut self.boards[index]
+    }
+
+    /// Record a finished game.
+    /// Returns true if the game made it into the best times, it is added with the last entered name
+    pub fn record(&mut self, key: &BoardKey, game: &GameRecord) -> bool {
+        let name = self.last_name.clone();
+        let board = self.board_mut(key);
+        board.played += 1;
+
+        if !game.won {
+            board.current_streak = 0;
+            return false;
+        }
+
+        board.won += 1;
+        board.current_streak += 1;
+        board.best_streak = board.best_streak.max(board.current_streak);
+        board.best_three_bv_per_second = board
+            .best_three_bv_per_second
+            .max(game.metrics.three_bv_per_second());
+
+        // Equal times keep the earlier game ahead
+        let rank = board
+            .best_times
+            .iter()
+            .position(|score| score.time > game.metrics.time)
+            .unwrap_or(board.best_times.len());
+
+        if rank >= Self::BEST_TIMES {
+            return false;
+        }
+
+        board.best_times.insert(
+            rank,
+            HighScore {
+                name,
+                time: game.metrics.time,
+                three_bv_per_second: game.metrics.three_bv_per_second(),
+                date: game.date,
+            },
+        );
+        board.best_times.truncate(Self::BEST_TIMES);
+        true
+    }
+
+    /// Set the name of the best time from the game started at `date`
+    pub fn set_name(&mut self, key: &BoardKey, date: u64, name: &str) {
+        self.last_name = name.to_string();
+
+        if let Some(score) = self
+            .board_mut(key)
+            .best_times
+            .iter_mut()
+            .find(|score| score.date == date)
+        {
+            score.name = name.to_string();
+        }
+    }
+}
@@ -0,0 +1,172 @@
+use ggez::{graphics, Context, GameResult};
+
+use crate::sprite_sheet::SpriteSheet;
+
+use super::Element;
+
+/// The state a button is drawn in
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub enum ButtonState {
+    Normal,
+    Hover,
+    Pressed,
+    Disabled,
+}
+
+/// The sprites for a wide button in the order normal, hover, pressed and disabled
+pub const WIDE_BUTTON_SPRITES: [&str; 4] = ["button00", "button02", "button01", "button03"];
+
+/// The sprites for a small square button in the order normal, hover, pressed and disabled
+pub const SMALL_BUTTON_SPRITES: [&str; 4] = ["button11", "button09", "button12", "button08"];
+
+impl ButtonState {
+    /// The index of the sprite used for the state
+    fn sprite_index(&self) -> usize {
+        match self {
+            ButtonState::Normal => 0,
+            ButtonState::Hover => 1,
+            ButtonState::Pressed => 2,
+            ButtonState::Disabled => 3,
+        }
+    }
+}
+
+/// # Button
+/// A clickable element that emits its message when the mouse is pressed and released on it.
+/// Hit testing uses the size of the normal sprite in the sprite sheet
+pub struct Button<M> {
+    element: Element,
+    sprites: [String; 4],
+    position: cgmath::Point2<f32>,
+    message: M,
+    state: ButtonState,
+}
+
+impl<M: Clone> Button<M> {
+    /// Create a new wide button with the top left corner at `position`
+    pub fn new(position: cgmath::Point2<f32>, message: M) -> Self {
+        Button {
+            element: Element::new_element(position).set_sprite(WIDE_BUTTON_SPRITES[0]),
+            sprites: [
+                WIDE_BUTTON_SPRITES[0].to_string(),
+                WIDE_BUTTON_SPRITES[1].to_string(),
+                WIDE_BUTTON_SPRITES[2].to_string(),
+                WIDE_BUTTON_SPRITES[3].to_string(),
+            ],
+            position,
+            message,
+            state: ButtonState::Normal,
+        }
+    }
+
+    /// Use other sprites for the states in the order normal, hover, pressed and disabled
+    pub fn set_sprites(mut self, sprites: [&str; 4]) -> Self {
+        self.sprites = [
+            sprites[0].to_string(),
+            sprites[1].to_string(),
+            sprites[2].to_string(),
+            sprites[3].to_string(),
+        ];
+        self.change_state(self.state);
+        self
+    }
+
+    pub fn set_label(
+        mut self,
+        label: &str,
+        font: &graphics::Font,
+        scale: &graphics::Scale,
+    ) -> Self {
+        self.element = self.element.set_label(label, font, scale);
+        self
+    }
+
+    /// A disabled button is drawn grayed out and does not emit any messages
+    pub fn set_enabled(&mut self, enabled: bool) {
+        if !enabled {
+            self.change_state(ButtonState::Disabled);
+        } else if self.state == ButtonState::Disabled {
+            self.change_state(ButtonState::Normal);
+        }
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.state != ButtonState::Disabled
+    }
+
+    pub fn state(&self) -> ButtonState {
+        self.state
+    }
+
+    /// The message emitted when the button is clicked
+    pub fn message(&self) -> &M {
+        &self.message
+    }
+
+    /// The area covered by the button
+    pub fn rect(&self, sprite_sheet: &SpriteSheet) -> graphics::Rect {
+        let size = sprite_sheet
+            .get_sprite_pixel_size(&self.sprites[ButtonState::Normal.sprite_index()])
+            .unwrap_or_else(|| cgmath::vec2(0.0, 0.0));
+
+        graphics::Rect::new(self.position.x, self.position.y, size.x, size.y)
+    }
+
+    /// Returns true if the point is on the button
+    pub fn contains(&self, point: cgmath::Point2<f32>, sprite_sheet: &SpriteSheet) -> bool {
+        self.rect(sprite_sheet).contains(point)
+    }
+
+    /// Update the hover state when the mouse moves
+    pub fn mouse_motion(&mut self, point: cgmath::Point2<f32>, sprite_sheet: &SpriteSheet) {
+        match (self.state, self.contains(point, sprite_sheet)) {
+            (ButtonState::Normal, true) => self.change_state(ButtonState::Hover),
+            (ButtonState::Hover, false) => self.change_state(ButtonState::Normal),
+            _ => {}
+        }
+    }
+
+    /// Press the button if the mouse is over it
+    pub fn mouse_down(&mut self, point: cgmath::Point2<f32>, sprite_sheet: &SpriteSheet) {
+        if self.is_enabled() && self.contains(point, sprite_sheet) {
+            self.change_state(ButtonState::Pressed);
+        }
+    }
+
+    /// Release the button and return its message if it was pressed and the mouse is still over it
+    pub fn mouse_up(
+        &mut self,
+        point: cgmath::Point2<f32>,
+        sprite_sheet: &SpriteSheet,
+    ) -> Option<M> {
+        if self.state != ButtonState::Pressed {
+            return None;
+        }
+
+        if self.contains(point, sprite_sheet) {
+            self.change_state(ButtonState::Hover);
+            Some(self.message.clone())
+        } else {
+            self.change_state(ButtonState::Normal);
+            None
+        }
+    }
+
+    pub fn draw(&self, ctx: &mut Context, sprite_sheet: &SpriteSheet) -> GameResult {
+        self.element.draw(ctx, sprite_sheet)
+    }
+
+    /// Change the state and the sprite that goes with it.
+    /// The shorter sprites are moved down so the bottom edge stays in place
+    fn change_state(&mut self, state: ButtonState) {
+        self.state = state;
+        self.element
+            .change_sprite(&self.sprites[state.sprite_index()]);
+
+        let offset = match state {
+            ButtonState::Pressed | ButtonState::Disabled => cgmath::vec2(0.0, 4.0),
+            ButtonState::Normal | ButtonState::Hover => cgmath::vec2(0.0, 0.0),
+        };
+        self.element.set_position(self.position + offset);
+    }
+}
@@ -89,6 +89,16 @@ impl Element {
         }
     }
 
+    /// Move the element to a new position
+    pub fn set_position(&mut self, position: cgmath::Point2<f32>) {
+        self.position = position;
+    }
+
+    /// Change the sprite of an element that has already been built
+    pub fn change_sprite(&mut self, sprite_name: &str) {
+        self.sprite_name = Some(sprite_name.to_string());
+    }
+
     pub fn set_sprite(mut self, sprite_name: &str) -> Self {
         self.sprite_name = Some(sprite_name.to_string());
         self
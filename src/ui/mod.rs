@@ -1,5 +1,7 @@
+mod button;
 mod elements;
 mod panel;
 
+pub use button::{Button, ButtonState, SMALL_BUTTON_SPRITES, WIDE_BUTTON_SPRITES};
 pub use elements::Element;
 pub use panel::Panel;
@@ -31,6 +31,23 @@ impl Panel {
         Self { rect, sprites }
     }
 
+    /// Create a panel using the panel sprites of the colored ui sheet
+    pub fn with_default_sprites(rect: graphics::Rect) -> Self {
+        let sprites = [
+            "panel_top_left".to_string(),
+            "panel_top".to_string(),
+            "panel_top_right".to_string(),
+            "panel_left".to_string(),
+            "panel_center".to_string(),
+            "panel_right".to_string(),
+            "panel_bottom_left".to_string(),
+            "panel_bottom".to_string(),
+            "panel_bottom_right".to_string(),
+        ];
+
+        Self::new(rect, sprites)
+    }
+
     pub fn draw(
         &self,
         ctx: &mut ggez::Context,
@@ -49,7 +66,7 @@ impl Panel {
         let sprites = self
             .sprites
             .iter()
-            .map(|s| (s, sprite_sheet.get_sprite_and_rect(&s)))
+            .map(|s| (s, sprite_sheet.get_sprite_and_rect(s)))
             .map(|o| {
                 o.1.ok_or_else(|| {
                     WrappedError::SheetError(format!("Sprite sheet does not have{}", o.0))
@@ -0,0 +1,39 @@
+use ggsweep::board::{bot, Board, Generation, Status, WinCondition};
+
+#[test]
+fn no_guess_boards_are_won_without_guessing() {
+    for seed in 0..20 {
+        let board = Board::new((9, 9), 10, seed).set_generation(Generation::NoGuess);
+        let game = bot::play(board);
+
+        assert_eq!(game.status, Status::Won, "seed {}", seed);
+        assert_eq!(game.guesses, 0, "seed {}", seed);
+    }
+}
+
+#[test]
+fn every_game_is_played_to_the_end() {
+    for seed in 0..20 {
+        let board = Board::new((16, 16), 40, seed);
+        let game = bot::play(board);
+
+        assert_ne!(game.status, Status::InGame, "seed {}", seed);
+    }
+}
+
+#[test]
+fn mines_are_flagged_to_win() {
+    for seed in 0..10 {
+        let board = Board::new((9, 9), 10, seed)
+            .set_generation(Generation::NoGuess)
+            .set_win_condition(WinCondition::FlagAllMines);
+
+        assert_eq!(bot::play(board).status, Status::Won, "seed {}", seed);
+    }
+}
+
+#[test]
+fn the_same_seed_plays_the_same_game() {
+    let play = || bot::play(Board::new((16, 16), 40, 7));
+    assert_eq!(play(), play());
+}
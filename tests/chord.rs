@@ -0,0 +1,70 @@
+use ggsweep::board::{Board, IndexType, Mark, SquareState, Status};
+
+/// A 4x3 board with the second square of the middle row opened
+fn open_board(mines: &[IndexType]) -> Board {
+    let mut board = Board::new((4, 3), mines.len(), 0).set_mines(mines.iter().copied());
+    board.reveal(5);
+    board
+}
+
+/// The opened square is a 2 from the mines above and below it, the top right mine is out of reach
+fn board() -> Board {
+    let board = open_board(&[0, 3, 10]);
+    assert_eq!(board.square(5), &SquareState::Open(2));
+    board
+}
+
+fn closed(board: &Board) -> Vec<usize> {
+    (0..board.len())
+        .filter(|i| matches!(board.square(*i), SquareState::Closed(_)))
+        .collect()
+}
+
+#[test]
+fn chording_opens_the_neighbors_when_the_flags_match() {
+    let mut board = board();
+    board.toggle_flag(0);
+    board.toggle_flag(10);
+    board.chord(5);
+
+    assert_eq!(closed(&board), vec![0, 3, 7, 10, 11]);
+    assert_eq!(board.square(1), &SquareState::Open(1));
+    assert_eq!(board.status(), Status::InGame);
+}
+
+#[test]
+fn chording_does_nothing_with_too_few_or_too_many_flags() {
+    let mut board = board();
+    board.toggle_flag(0);
+    board.chord(5);
+    assert_eq!(closed(&board).len(), 11);
+
+    board.toggle_flag(10);
+    board.toggle_flag(1);
+    board.chord(5);
+    assert_eq!(closed(&board).len(), 11);
+    assert_eq!(board.status(), Status::InGame);
+}
+
+#[test]
+fn chording_closed_squares_does_nothing() {
+    let mut board = board();
+    board.toggle_flag(0);
+    board.toggle_flag(10);
+    board.chord(1);
+
+    assert_eq!(closed(&board).len(), 11);
+    assert_eq!(board.square(0), &SquareState::Closed(Mark::Flag));
+}
+
+#[test]
+fn chording_around_a_wrong_flag_loses() {
+    // The mine is next to the flag below the opened square
+    let mut board = open_board(&[0, 3, 9]);
+    board.toggle_flag(0);
+    board.toggle_flag(10);
+    board.chord(5);
+
+    assert_eq!(board.status(), Status::Lost);
+    assert_eq!(board.detonated(), Some(9));
+}
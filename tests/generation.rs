@@ -0,0 +1,72 @@
+use ggsweep::board::{Board, Generation, IndexType};
+
+fn neighbors(board: &Board, index: IndexType) -> Vec<IndexType> {
+    board
+        .get_neighbors(index)
+        .iter()
+        .flatten()
+        .copied()
+        .collect()
+}
+
+#[test]
+fn safe_zone_keeps_the_first_square_and_its_neighbors_free() {
+    for seed in 0..20 {
+        for &first in &[0, 4, 40, 80] {
+            let mut board = Board::new((9, 9), 30, seed).set_generation(Generation::SafeZone);
+            board.reveal(first);
+
+            assert_eq!(board.mines().len(), 30);
+            assert!(!board.is_mine(first), "seed {} first {}", seed, first);
+            for neighbor in neighbors(&board, first) {
+                assert!(!board.is_mine(neighbor), "seed {} first {}", seed, first);
+            }
+        }
+    }
+}
+
+#[test]
+fn safe_zone_fits_as_many_mines_as_the_squares_outside_it() {
+    for seed in 0..20 {
+        let mut board = Board::new((9, 9), 72, seed).set_generation(Generation::SafeZone);
+        board.reveal(40);
+
+        assert_eq!(board.mines().len(), 72);
+        assert!(!board.is_mine(40));
+        assert!(neighbors(&board, 40).iter().all(|n| !board.is_mine(*n)));
+    }
+}
+
+#[test]
+fn dense_boards_only_spare_the_first_square() {
+    for seed in 0..20 {
+        let mut board = Board::new((9, 9), 75, seed).set_generation(Generation::SafeZone);
+        board.reveal(40);
+
+        assert_eq!(board.mines().len(), 75);
+        assert!(!board.is_mine(40), "seed {}", seed);
+        // Only 5 of the other 80 squares are safe so some neighbors have to be mines
+        assert!(neighbors(&board, 40).iter().any(|n| board.is_mine(*n)));
+    }
+}
+
+#[test]
+fn classic_only_spares_the_first_square() {
+    for seed in 0..20 {
+        let mut board = Board::new((3, 3), 8, seed);
+        board.reveal(4);
+
+        assert!(!board.is_mine(4), "seed {}", seed);
+        assert_eq!(board.mines().len(), 8);
+    }
+}
+
+#[test]
+fn a_seed_always_places_the_same_mines() {
+    let mut board = Board::new((9, 9), 10, 42);
+    board.reveal(40);
+
+    let mut mines: Vec<IndexType> = board.mines().iter().copied().collect();
+    mines.sort_unstable();
+    assert_eq!(mines, vec![11, 21, 22, 32, 47, 48, 55, 60, 75, 80]);
+}
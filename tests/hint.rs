@@ -0,0 +1,192 @@
+use std::{collections::HashSet, sync::atomic::AtomicBool};
+
+use ggsweep::board::{
+    probability::{self, ProbabilityError},
+    Board, Hint, IndexType, Layout, SquareState, Status,
+};
+
+/// The closed squares of the board
+fn closed_squares(board: &Board) -> Vec<IndexType> {
+    (0..board.len())
+        .filter(|i| matches!(board.square(*i), SquareState::Closed(_)))
+        .collect()
+}
+
+/// Count how often every closed square is a mine over all mine placements that agree with the numbers
+fn brute_force_probabilities(board: &Board) -> Vec<f64> {
+    let closed = closed_squares(board);
+    let mut mine_counts = vec![0.0; board.len()];
+    let mut placements = 0.0;
+
+    let agrees = |mines: &HashSet<IndexType>| {
+        (0..board.len()).all(|i| match board.square(i) {
+            SquareState::Open(count) => {
+                board
+                    .get_neighbors(i)
+                    .iter()
+                    .flatten()
+                    .filter(|n| mines.contains(n))
+                    .count()
+                    == *count as usize
+            }
+            SquareState::Closed(_) => true,
+        })
+    };
+
+    // Go through every subset of the closed squares with the right amount of mines
+    let mut picked: Vec<usize> = (0..board.number_of_mines()).collect();
+    loop {
+        let mines: HashSet<IndexType> = picked.iter().map(|&p| closed[p]).collect();
+        if agrees(&mines) {
+            placements += 1.0;
+            for mine in &mines {
+                mine_counts[*mine] += 1.0;
+            }
+        }
+
+        let mut i = picked.len();
+        loop {
+            if i == 0 {
+                return mine_counts.iter().map(|c| c / placements).collect();
+            }
+            i -= 1;
+            if picked[i] < closed.len() - picked.len() + i {
+                break;
+            }
+        }
+        picked[i] += 1;
+        for j in i + 1..picked.len() {
+            picked[j] = picked[j - 1] + 1;
+        }
+    }
+}
+
+#[test]
+fn first_hint_is_the_middle() {
+    let board = Board::new((9, 9), 10, 1);
+    let hint = Hint::find(&board).unwrap();
+    assert_eq!(hint, Hint::FirstSquare(40));
+    assert_eq!(
+        hint.explanation(),
+        "Safe: the first square opened is never a mine"
+    );
+}
+
+#[test]
+fn hints_are_right() {
+    for seed in 0..20 {
+        let mut board = Board::new((9, 9), 10, seed);
+        board.reveal(40);
+
+        while board.status() == Status::InGame {
+            match Hint::find(&board).unwrap() {
+                Hint::Deduction(deduction) => {
+                    assert_eq!(deduction.is_mine, board.is_mine(deduction.index));
+                    if deduction.is_mine {
+                        board.toggle_flag(deduction.index);
+                    } else {
+                        board.reveal(deduction.index);
+                    }
+                }
+                // The probabilities can prove squares the rules of the solver can not
+                Hint::FirstSquare(index)
+                | Hint::Guess {
+                    index,
+                    probability: 0.0,
+                } => {
+                    assert!(!board.is_mine(index));
+                    board.reveal(index);
+                }
+                Hint::Guess { probability, .. } => {
+                    assert!(probability < 1.0);
+                    break;
+                }
+            }
+        }
+
+        assert_ne!(board.status(), Status::Lost, "seed {}", seed);
+    }
+}
+
+#[test]
+fn probabilities_match_brute_force() {
+    for seed in 0..10 {
+        let mut board = Board::new((5, 5), 4, seed);
+        board.reveal(0);
+        if board.status() != Status::InGame {
+            continue;
+        }
+
+        let expected = brute_force_probabilities(&board);
+        let probabilities = probability::mine_probabilities(&board, &HashSet::new()).unwrap();
+
+        for index in closed_squares(&board) {
+            let probability = probabilities[index].unwrap();
+            assert!(
+                (probability - expected[index]).abs() < 1e-9,
+                "seed {} square {}: {} != {}",
+                seed,
+                index,
+                probability,
+                expected[index]
+            );
+        }
+    }
+}
+
+#[test]
+fn probabilities_add_up_to_the_mines() {
+    let mut board = Board::new((30, 16), 99, 3);
+    board.reveal(240);
+
+    let probabilities = probability::mine_probabilities(&board, &HashSet::new()).unwrap();
+    let sum: f64 = probabilities.iter().flatten().sum();
+    assert!((sum - 99.0).abs() < 1e-6, "{}", sum);
+    assert!(probabilities
+        .iter()
+        .enumerate()
+        .all(|(i, p)| p.is_some() == matches!(board.square(i), SquareState::Closed(_))));
+}
+
+#[test]
+fn wrong_known_mines_have_no_probabilities() {
+    let mut board = Board::new((9, 9), 10, 2);
+    board.reveal(40);
+
+    let all_closed: HashSet<IndexType> = closed_squares(&board).into_iter().collect();
+    assert_eq!(
+        probability::mine_probabilities(&board, &all_closed),
+        Err(ProbabilityError::NoPlacement)
+    );
+}
+
+#[test]
+fn cancelled_guesses_give_no_hint() {
+    // The mine is in one of the two left squares so the hint has to be a guess
+    let layout = Layout::from_text("3x2\n*..\n...\n").unwrap();
+    let mut board = Board::new((3, 2), 1, 0).set_layout(&layout);
+    board.reveal(5);
+
+    assert!(matches!(
+        Hint::find_cancellable(&board, &AtomicBool::new(false)),
+        Some(Hint::Guess { .. })
+    ));
+    assert_eq!(Hint::find_cancellable(&board, &AtomicBool::new(true)), None);
+}
+
+#[test]
+fn wide_frontiers_give_up_instead_of_running_forever() {
+    // The open middle row is all numbers with closed rows above and below it
+    let width = 60;
+    let mines: Vec<IndexType> = (0..width).step_by(2).collect();
+    let mut board = Board::new((width, 3), mines.len(), 0).set_mines(mines);
+    for index in width..width * 2 {
+        board.reveal(index);
+    }
+    assert_eq!(closed_squares(&board).len(), width * 2);
+
+    assert_eq!(
+        probability::mine_probabilities(&board, &HashSet::new()),
+        Err(ProbabilityError::TooManyCombinations)
+    );
+}
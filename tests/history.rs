@@ -0,0 +1,91 @@
+use ggsweep::board::{Board, History, Layout, Mark, Move, SquareState, Status};
+
+fn same(a: &Board, b: &Board) {
+    assert_eq!(a.squares(), b.squares());
+    assert_eq!(a.mines(), b.mines());
+    assert_eq!(a.is_generated(), b.is_generated());
+    assert_eq!(a.status(), b.status());
+    assert_eq!(a.detonated(), b.detonated());
+}
+
+#[test]
+fn undo_puts_back_the_changed_squares() {
+    let mut board = Board::new((9, 9), 10, 5);
+    let mut history = History::new();
+
+    history.apply(&mut board, Move::Reveal(40));
+    let after_reveal = board.clone();
+    let closed = (0..board.len())
+        .find(|i| matches!(board.square(*i), SquareState::Closed(_)))
+        .unwrap();
+
+    history.apply(&mut board, Move::Flag(closed));
+    assert_eq!(board.square(closed), &SquareState::Closed(Mark::Flag));
+
+    assert!(history.undo(&mut board, false));
+    same(&board, &after_reveal);
+
+    assert!(history.redo(&mut board));
+    assert_eq!(board.square(closed), &SquareState::Closed(Mark::Flag));
+    assert!(history.used_undo());
+}
+
+#[test]
+fn undoing_the_first_reveal_removes_the_mines() {
+    let mut board = Board::new((9, 9), 10, 5);
+    let mut history = History::new();
+
+    history.apply(&mut board, Move::Reveal(40));
+    let revealed = board.clone();
+
+    assert!(history.undo(&mut board, false));
+    same(&board, &Board::new((9, 9), 10, 5));
+
+    assert!(history.redo(&mut board));
+    same(&board, &revealed);
+}
+
+#[test]
+fn undoing_a_loss_puts_back_the_status() {
+    let layout = Layout::from_text("3x3\n*..\n...\n..*\n").unwrap();
+    let mut board = Board::new((3, 3), 2, 0).set_layout(&layout);
+    let mut history = History::new();
+
+    history.apply(&mut board, Move::Reveal(4));
+    let before = board.clone();
+    history.apply(&mut board, Move::Reveal(0));
+    assert_eq!(board.status(), Status::Lost);
+    assert_eq!(board.detonated(), Some(0));
+
+    assert!(!history.undo(&mut board, false));
+    assert!(history.undo(&mut board, true));
+    same(&board, &before);
+    assert!(board.is_generated());
+}
+
+#[test]
+fn moves_that_change_nothing_are_not_remembered() {
+    let mut board = Board::new((9, 9), 10, 5);
+    let mut history = History::new();
+
+    history.apply(&mut board, Move::Reveal(40));
+    history.apply(&mut board, Move::Reveal(40));
+    history.apply(&mut board, Move::Flag(40));
+
+    assert!(history.undo(&mut board, false));
+    assert!(!history.can_undo(&board, false));
+}
+
+#[test]
+fn a_new_move_clears_the_redo() {
+    let mut board = Board::new((9, 9), 10, 5);
+    let mut history = History::new();
+
+    history.apply(&mut board, Move::Reveal(40));
+    history.apply(&mut board, Move::Flag(0));
+    history.undo(&mut board, false);
+    history.apply(&mut board, Move::Flag(8));
+
+    assert!(!history.redo(&mut board));
+    assert_eq!(board.square(0), &SquareState::Closed(Mark::None));
+}
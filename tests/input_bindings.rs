@@ -0,0 +1,93 @@
+use ggez::event::{KeyCode, KeyMods, MouseButton};
+use ggsweep::config::{Action, InputBindings, KeyPress};
+
+#[test]
+fn default_bindings() {
+    let bindings = InputBindings::default();
+    assert_eq!(
+        bindings.key_action(KeyCode::Space, KeyMods::NONE),
+        Some(Action::Reveal)
+    );
+    assert_eq!(
+        bindings.mouse_action(MouseButton::Right),
+        Some(Action::Flag)
+    );
+    assert_eq!(
+        bindings.key_action(KeyCode::D, KeyMods::NONE),
+        Some(Action::Chord)
+    );
+    assert_eq!(
+        bindings.key_action(KeyCode::H, KeyMods::NONE),
+        Some(Action::Hint)
+    );
+    assert_eq!(bindings.mouse_action(MouseButton::Other(4)), None);
+}
+
+#[test]
+fn ctrl_has_to_match() {
+    let bindings = InputBindings::default();
+    assert_eq!(
+        bindings.key_action(KeyCode::Z, KeyMods::CTRL),
+        Some(Action::Undo)
+    );
+    assert_eq!(bindings.key_action(KeyCode::Z, KeyMods::NONE), None);
+    assert_eq!(bindings.key_action(KeyCode::F, KeyMods::CTRL), None);
+}
+
+#[test]
+fn binding_removes_it_from_other_actions() {
+    let mut bindings = InputBindings::default();
+    bindings.bind_key(Action::Pause, KeyPress::new(KeyCode::F));
+    bindings.bind_mouse(Action::Chord, MouseButton::Left);
+
+    assert_eq!(bindings.flag.key, None);
+    assert_eq!(bindings.reveal.mouse, None);
+    assert_eq!(
+        bindings.key_action(KeyCode::F, KeyMods::NONE),
+        Some(Action::Pause)
+    );
+    assert_eq!(
+        bindings.mouse_action(MouseButton::Left),
+        Some(Action::Chord)
+    );
+}
+
+#[test]
+fn missing_actions_keep_their_default() {
+    let bindings: InputBindings =
+        ron::de::from_str("(flag: (key: Some((key: G)), mouse: None))").unwrap();
+
+    assert_eq!(bindings.flag.key, Some(KeyPress::new(KeyCode::G)));
+    assert_eq!(bindings.flag.mouse, None);
+    assert_eq!(bindings.reveal, InputBindings::default().reveal);
+}
+
+#[test]
+fn default_keys_do_not_move_the_cursor() {
+    let bindings = InputBindings::default();
+
+    for action in Action::ALL.iter() {
+        if let Some(press) = bindings.binding(*action).key {
+            assert_eq!(
+                InputBindings::movement(press.key),
+                None,
+                "{} is bound to {}",
+                action.name(),
+                press.name()
+            );
+        }
+    }
+}
+
+#[test]
+fn movement_keys() {
+    for &key in &[KeyCode::Up, KeyCode::I] {
+        assert_eq!(InputBindings::movement(key), Some((0, -1)));
+    }
+    for &key in &[KeyCode::Right, KeyCode::L] {
+        assert_eq!(InputBindings::movement(key), Some((1, 0)));
+    }
+    assert_eq!(InputBindings::movement(KeyCode::Space), None);
+    assert_eq!(InputBindings::movement(KeyCode::D), None);
+    assert_eq!(InputBindings::movement(KeyCode::H), None);
+}
@@ -0,0 +1,159 @@
+use ggsweep::{
+    board::{Board, Generation, Layout, Mark, SquareState, Status},
+    config::GameConfig,
+    replay::Replay,
+};
+
+const TEXT: &str = "4x3\n.*..\n....\n*..*\n";
+
+#[test]
+fn text_round_trip() {
+    let layout = Layout::from_text(TEXT).unwrap();
+
+    assert_eq!(layout.size(), (4, 3));
+    assert_eq!(layout.mines().collect::<Vec<_>>(), vec![1, 8, 11]);
+    assert_eq!(layout.to_text(), TEXT);
+}
+
+#[test]
+fn mbf_round_trip() {
+    let layout = Layout::from_text(TEXT).unwrap();
+    let bytes = layout.to_mbf().unwrap();
+
+    // Width, height, the amount of mines in two bytes and the x and y of every mine
+    assert_eq!(bytes, vec![4, 3, 0, 3, 1, 0, 0, 2, 3, 2]);
+    assert_eq!(Layout::from_mbf(&bytes).unwrap(), layout);
+}
+
+#[test]
+fn broken_layouts_are_rejected() {
+    assert!(Layout::from_text("").is_err());
+    assert!(Layout::from_text("4 by 3\n....").is_err());
+    assert!(Layout::from_text("4x2\n....\n...").is_err());
+    assert!(Layout::from_text("4x2\n....").is_err());
+    assert!(Layout::from_text("4x1\n....\n....").is_err());
+    assert!(Layout::from_text("4x1\n..o.").is_err());
+
+    assert!(Layout::from_mbf(&[4, 3, 0]).is_err());
+    assert!(Layout::from_mbf(&[4, 3, 0, 2, 1, 0]).is_err());
+    assert!(Layout::from_mbf(&[4, 3, 0, 1, 4, 0]).is_err());
+    assert!(Layout::from_mbf(&[4, 3, 0, 2, 1, 0, 1, 0]).is_err());
+}
+
+#[test]
+fn layout_boards_keep_their_mines() {
+    let layout = Layout::from_text(TEXT).unwrap();
+    let mut board = Board::new((9, 9), 10, 0).set_layout(&layout);
+
+    assert_eq!(board.size(), (4, 3));
+    assert_eq!(board.number_of_mines(), 3);
+    assert!(board.is_generated());
+    assert!(!board.is_started());
+
+    board.reveal(2);
+    assert!(board.is_started());
+    assert_eq!(Layout::from_board(&board), Some(layout));
+}
+
+#[test]
+fn the_first_square_of_a_layout_can_be_a_mine() {
+    let layout = Layout::from_text(TEXT).unwrap();
+    let mut board = Board::new((4, 3), 3, 0).set_layout(&layout);
+
+    board.reveal(1);
+    assert_eq!(board.status(), Status::Lost);
+}
+
+#[test]
+fn boards_without_mines_have_no_layout() {
+    let board = Board::new((9, 9), 10, 0);
+    assert_eq!(Layout::from_board(&board), None);
+}
+
+#[test]
+fn replays_play_the_layout_again() {
+    let layout = Layout::from_text(TEXT).unwrap();
+    let game_config = GameConfig {
+        game_size: layout.size(),
+        number_of_mines: layout.number_of_mines(),
+        ..ron::de::from_str::<GameConfig>(include_str!("../resources/config.ron")).unwrap()
+    };
+    let replay = Replay::new(game_config, 0).set_layout(layout.clone());
+
+    let mut board = replay.new_board();
+    board.toggle_flag(8);
+    assert_eq!(board.square(8), &SquareState::Closed(Mark::Flag));
+    assert_eq!(
+        board.mines(),
+        Board::new((4, 3), 3, 0).set_layout(&layout).mines()
+    );
+}
+
+#[test]
+fn restarted_boards_keep_their_mines() {
+    for seed in 0..10 {
+        let mut board = Board::new((9, 9), 10, seed).set_generation(Generation::SafeZone);
+        board.reveal(40);
+        board.toggle_flag(0);
+
+        let mut again = board.restart();
+        assert!(again
+            .squares()
+            .iter()
+            .all(|s| s == &SquareState::Closed(Mark::None)));
+        assert_eq!(again.status(), Status::InGame);
+
+        // The first click of the new game is somewhere else but the mines stay the same
+        let first = (0..again.len())
+            .find(|i| *i != 40 && !again.is_mine(*i))
+            .unwrap();
+        again.reveal(first);
+        assert_eq!(again.mines(), board.mines(), "seed {}", seed);
+        assert_eq!(again.seed(), seed);
+    }
+}
+
+#[test]
+fn restarted_boards_without_mines_start_over() {
+    let board = Board::new((9, 9), 10, 3);
+    assert!(!board.restart().is_generated());
+}
+
+#[test]
+fn mines_outside_of_the_board_are_rejected() {
+    assert!(Layout::new((4, 3), vec![0, 11]).is_ok());
+    assert!(Layout::new((4, 3), vec![0, 12]).is_err());
+}
+
+#[test]
+fn layouts_of_any_size_can_be_played() {
+    assert!(
+        Layout::from_text("5x5\n*....\n.....\n..*..\n.....\n....*\n")
+            .unwrap()
+            .validate()
+            .is_ok()
+    );
+    assert!(Layout::from_text("1x1\n.\n").unwrap().validate().is_ok());
+    assert!(Layout::new((200, 150), vec![0, 29_999])
+        .unwrap()
+        .validate()
+        .is_ok());
+    assert!(Layout::new((3, 1), vec![]).unwrap().validate().is_ok());
+}
+
+#[test]
+fn layouts_without_squares_or_safe_squares_can_not_be_played() {
+    assert!(Layout::new((0, 3), vec![]).unwrap().validate().is_err());
+    assert!(Layout::from_text("2x1\n**\n").unwrap().validate().is_err());
+}
+
+#[test]
+fn layouts_are_checked_when_they_are_read() {
+    let layout = Layout::from_text(TEXT).unwrap();
+    let text = ron::ser::to_string(&layout).unwrap();
+    assert_eq!(ron::de::from_str::<Layout>(&text).unwrap(), layout);
+
+    assert!(ron::de::from_str::<Layout>("(size: (4, 3), mines: [0, 12])").is_err());
+    assert!(ron::de::from_str::<Layout>("(size: (2, 1), mines: [0, 1])").is_err());
+    assert!(ron::de::from_str::<Layout>("(size: (0, 3), mines: [])").is_err());
+}
@@ -0,0 +1,91 @@
+use ggsweep::board::{Board, Mark, SquareState, Status, WinCondition};
+
+/// A 5x5 board with 3 mines where the corner has been revealed
+fn started_board(question_marks: bool) -> Board {
+    let mut board = Board::new((5, 5), 3, 7).set_question_marks(question_marks);
+    board.reveal(0);
+    board
+}
+
+/// A closed square that is not a mine
+fn closed_safe_square(board: &Board) -> usize {
+    (0..board.len())
+        .find(|&i| board.square(i) == &SquareState::Closed(Mark::None) && !board.is_mine(i))
+        .unwrap()
+}
+
+#[test]
+fn marks_cycle_through_question_marks_when_enabled() {
+    let mut board = started_board(true);
+    let index = closed_safe_square(&board);
+
+    for mark in [Mark::Flag, Mark::Question, Mark::None].iter() {
+        board.toggle_flag(index);
+        assert_eq!(board.square(index), &SquareState::Closed(*mark));
+    }
+}
+
+#[test]
+fn marks_skip_question_marks_when_disabled() {
+    let mut board = started_board(false);
+    let index = closed_safe_square(&board);
+
+    board.toggle_flag(index);
+    board.toggle_flag(index);
+    assert_eq!(board.square(index), &SquareState::Closed(Mark::None));
+}
+
+#[test]
+fn question_marks_are_not_flags() {
+    let mut board = Board::new((5, 5), 3, 7)
+        .set_question_marks(true)
+        .set_win_condition(WinCondition::FlagAllMines);
+    board.reveal(0);
+
+    let mines: Vec<usize> = board.mines().iter().copied().collect();
+    for &mine in &mines {
+        board.toggle_flag(mine);
+        board.toggle_flag(mine);
+    }
+
+    assert!(board.get_flagged_squares().is_empty());
+    assert_eq!(board.status(), Status::InGame);
+
+    // From the question mark through no mark to a flag
+    for &mine in &mines {
+        board.toggle_flag(mine);
+        board.toggle_flag(mine);
+    }
+    assert_eq!(board.status(), Status::Won);
+}
+
+#[test]
+fn question_marked_squares_can_be_revealed() {
+    let mut board = started_board(true);
+    let index = closed_safe_square(&board);
+
+    board.toggle_flag(index);
+    board.reveal(index);
+    assert_eq!(board.square(index), &SquareState::Closed(Mark::Flag));
+
+    board.toggle_flag(index);
+    board.reveal(index);
+    assert!(matches!(board.square(index), SquareState::Open(_)));
+}
+
+#[test]
+fn marks_are_saved_with_the_board() {
+    let mut board = started_board(true);
+    let index = closed_safe_square(&board);
+    board.toggle_flag(index);
+    board.toggle_flag(index);
+
+    let text = ron::ser::to_string(&board).unwrap();
+    let mut loaded: Board = ron::de::from_str(&text).unwrap();
+    assert_eq!(loaded.squares(), board.squares());
+
+    // The loaded board still cycles through question marks
+    loaded.toggle_flag(index);
+    loaded.toggle_flag(index);
+    assert_eq!(loaded.square(index), &SquareState::Closed(Mark::Flag));
+}
@@ -0,0 +1,44 @@
+use std::time::Duration;
+
+use ggsweep::board::{Clicks, Metrics, Move};
+
+#[test]
+fn metrics_of_a_game() {
+    let mut clicks = Clicks::default();
+    for player_move in [
+        Move::Reveal(0),
+        Move::Flag(1),
+        Move::Chord(2),
+        Move::Reveal(3),
+    ]
+    .iter()
+    {
+        clicks.add(*player_move);
+    }
+    assert_eq!(
+        clicks,
+        Clicks {
+            left: 2,
+            right: 1,
+            chord: 1
+        }
+    );
+
+    let metrics = Metrics {
+        time: Duration::from_secs(2),
+        three_bv: 8,
+        solved_three_bv: 2,
+        clicks,
+    };
+    assert_eq!(metrics.three_bv_per_second(), 1.0);
+    assert_eq!(metrics.efficiency(), 50.0);
+    assert_eq!(metrics.ioe(), 2.0);
+}
+
+#[test]
+fn metrics_without_time_or_clicks_are_zero() {
+    let metrics = Metrics::default();
+    assert_eq!(metrics.three_bv_per_second(), 0.0);
+    assert_eq!(metrics.efficiency(), 0.0);
+    assert_eq!(metrics.ioe(), 0.0);
+}
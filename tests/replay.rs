@@ -0,0 +1,100 @@
+use std::time::Duration;
+
+use ggsweep::{
+    board::{History, Move, Status},
+    config::GameConfig,
+    replay::{Replay, ReplayAction},
+};
+
+fn game_config(practice: bool) -> GameConfig {
+    ron::de::from_str(&format!(
+        "GameConfig(
+            game_size: (9, 9),
+            number_of_mines: 10,
+            square_size: 32.0,
+            generation: SafeZone,
+            practice: {},
+            colors: (
+                square: (0, 191, 255),
+                selected_square: (100, 200, 255),
+                mine_square: (255, 50, 50),
+            ),
+        )",
+        practice
+    ))
+    .unwrap()
+}
+
+/// Play the actions on a new board the way the replay state does
+fn play(replay: &Replay) -> (ggsweep::board::Board, History) {
+    let mut board = replay.new_board();
+    let mut history = History::new();
+    for timed in &replay.actions {
+        replay.apply(timed.action, &mut board, &mut history);
+    }
+    (board, history)
+}
+
+#[test]
+fn replay_rebuilds_the_same_board() {
+    let mut replay = Replay::new(game_config(false), 42);
+    let mut board = replay.new_board();
+    let mut history = History::new();
+
+    let moves = [
+        Move::Reveal(40),
+        Move::Flag(0),
+        Move::Reveal(80),
+        Move::Reveal(8),
+    ];
+    for (i, player_move) in moves.iter().enumerate() {
+        history.apply(&mut board, *player_move);
+        replay.record(
+            Duration::from_secs(i as u64),
+            ReplayAction::Move(*player_move),
+        );
+    }
+
+    let (replayed, _) = play(&replay);
+    assert_eq!(replayed.squares(), board.squares());
+    assert_eq!(replayed.mines(), board.mines());
+    assert_eq!(replay.duration(), Duration::from_secs(3));
+}
+
+#[test]
+fn replay_survives_saving() {
+    let mut replay = Replay::new(game_config(false), 7);
+    replay.record(
+        Duration::from_millis(0),
+        ReplayAction::Move(Move::Reveal(10)),
+    );
+    replay.record(
+        Duration::from_millis(1500),
+        ReplayAction::Move(Move::Flag(3)),
+    );
+
+    let text = ron::ser::to_string(&replay).unwrap();
+    let loaded: Replay = ron::de::from_str(&text).unwrap();
+
+    assert_eq!(loaded.actions, replay.actions);
+    assert_eq!(play(&loaded).0.squares(), play(&replay).0.squares());
+}
+
+#[test]
+fn replay_plays_undo_after_loss_in_practice() {
+    let mut replay = Replay::new(game_config(true), 3);
+    let mut board = replay.new_board();
+    board.reveal(40);
+    let mine = *board.mines().iter().next().unwrap();
+
+    replay.record(Duration::from_secs(0), ReplayAction::Move(Move::Reveal(40)));
+    replay.record(
+        Duration::from_secs(1),
+        ReplayAction::Move(Move::Reveal(mine)),
+    );
+    replay.record(Duration::from_secs(2), ReplayAction::Undo);
+
+    let (replayed, history) = play(&replay);
+    assert_eq!(replayed.status(), Status::InGame);
+    assert!(history.used_undo());
+}
@@ -0,0 +1,62 @@
+use std::time::Duration;
+
+use ggsweep::{
+    board::{Board, History, Move, SquareState},
+    config::GameConfig,
+    replay::{Replay, ReplayAction},
+    save_game::SaveGame,
+};
+
+fn save() -> SaveGame {
+    let game_config =
+        ron::de::from_str::<GameConfig>(include_str!("../resources/config.ron")).unwrap();
+    let mut replay = Replay::new(game_config.clone(), 11);
+    let mut board = replay.new_board();
+    let mut history = History::new();
+
+    history.apply(&mut board, Move::Reveal(0));
+    replay.record(Duration::from_secs(0), ReplayAction::Move(Move::Reveal(0)));
+    let closed = (0..board.len())
+        .find(|i| matches!(board.square(*i), SquareState::Closed(_)))
+        .unwrap();
+    history.apply(&mut board, Move::Flag(closed));
+    replay.record(
+        Duration::from_secs(1),
+        ReplayAction::Move(Move::Flag(closed)),
+    );
+    history.undo(&mut board, false);
+    replay.record(Duration::from_secs(2), ReplayAction::Undo);
+
+    SaveGame::new(game_config, board, history, replay, Duration::from_secs(3))
+        .set_hints(2)
+        .set_used_analysis(true)
+        .set_loaded(true)
+}
+
+fn same_board(a: &Board, b: &Board) {
+    assert_eq!(a.squares(), b.squares());
+    assert_eq!(a.mines(), b.mines());
+    assert_eq!(a.status(), b.status());
+}
+
+#[test]
+fn save_game_round_trip() {
+    let mut save = save();
+    let text = ron::ser::to_string_pretty(&save, ron::ser::PrettyConfig::new()).unwrap();
+    let mut loaded: SaveGame = ron::de::from_str(&text).unwrap();
+
+    assert_eq!(loaded.version, SaveGame::VERSION);
+    assert_eq!(loaded.game_config.game_size, save.game_config.game_size);
+    same_board(&loaded.board, &save.board);
+    assert_eq!(loaded.replay.actions, save.replay.actions);
+    assert_eq!(loaded.elapsed, save.elapsed);
+    assert_eq!(loaded.hints, 2);
+    assert!(loaded.used_analysis);
+    assert!(loaded.loaded);
+
+    // The undone flag can still be redone after loading
+    assert!(save.history.redo(&mut save.board));
+    assert!(loaded.history.redo(&mut loaded.board));
+    same_board(&loaded.board, &save.board);
+    assert!(loaded.history.used_undo());
+}
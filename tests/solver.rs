@@ -0,0 +1,98 @@
+use ggsweep::board::{
+    solver::{self, Deduction, Rule, Solver},
+    Board, IndexType,
+};
+
+fn board(size: (usize, usize), mines: &[IndexType]) -> Board {
+    Board::new(size, mines.len(), 0).set_mines(mines.iter().copied())
+}
+
+fn deduction(deductions: &[Deduction], index: usize) -> Option<(bool, Rule)> {
+    deductions
+        .iter()
+        .find(|d| d.index == index)
+        .map(|d| (d.is_mine, d.rule))
+}
+
+#[test]
+fn single_square_finds_mines() {
+    let mut board = board((3, 1), &[1]);
+    board.reveal(0);
+
+    let deductions = Solver::new().deduce(&board);
+    assert_eq!(deduction(&deductions, 1), Some((true, Rule::SingleSquare)));
+    assert_eq!(deduction(&deductions, 2), None);
+}
+
+#[test]
+fn single_square_finds_safe_squares_next_to_known_mines() {
+    let mut board = board((3, 1), &[0]);
+    board.reveal(1);
+
+    let mut solver = Solver::new();
+    assert!(solver.deduce(&board).is_empty());
+
+    solver.mark_mine(0);
+    let deductions = solver.deduce(&board);
+    assert_eq!(deduction(&deductions, 2), Some((false, Rule::SingleSquare)));
+}
+
+/// A 4x2 board with mines at 0 and 2 in the top row and the bottom row reading 1 2 1 1
+fn pairs_board() -> Board {
+    let mut board = board((4, 2), &[0, 2]);
+    for index in 4..8 {
+        board.reveal(index);
+    }
+    board
+}
+
+#[test]
+fn subset_compares_numbers_inside_other_numbers() {
+    let deductions = Solver::new().deduce(&pairs_board());
+
+    assert_eq!(deduction(&deductions, 2), Some((true, Rule::Subset)));
+    assert_eq!(deduction(&deductions, 1), Some((false, Rule::Subset)));
+}
+
+#[test]
+fn overlap_compares_numbers_sharing_some_squares() {
+    let deductions = Solver::new().deduce(&pairs_board());
+
+    assert_eq!(deduction(&deductions, 0), Some((true, Rule::Overlap)));
+    assert_eq!(deduction(&deductions, 3), Some((false, Rule::Overlap)));
+}
+
+#[test]
+fn mine_count_decides_squares_away_from_the_numbers() {
+    // Opening the bottom right corner leaves two 1s around the squares below and next to the mine
+    let mut board = board((4, 2), &[1]);
+    board.reveal(7);
+
+    let deductions = Solver::new().deduce(&board);
+    assert_eq!(deduction(&deductions, 0), Some((false, Rule::MineCount)));
+    assert_eq!(deduction(&deductions, 4), Some((false, Rule::MineCount)));
+    assert_eq!(deductions.len(), 2);
+}
+
+#[test]
+fn boards_without_guesses_are_solvable() {
+    assert!(solver::is_solvable(&board((3, 1), &[0]), 2));
+    assert!(solver::is_solvable(&board((4, 2), &[0, 4]), 7));
+}
+
+#[test]
+fn a_forced_fifty_fifty_is_not_solvable() {
+    // The mine is in one of the two left squares and both numbers touch both of them
+    let board = board((3, 2), &[0]);
+    assert!(!solver::is_solvable(&board, 5));
+}
+
+#[test]
+fn deductions_come_in_the_same_order_every_time() {
+    let board = pairs_board();
+    let first = Solver::new().deduce(&board);
+
+    for _ in 0..20 {
+        assert_eq!(Solver::new().deduce(&board), first);
+    }
+}
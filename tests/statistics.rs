@@ -0,0 +1,113 @@
+use std::time::Duration;
+
+use ggsweep::{
+    board::{Clicks, Generation, Metrics, Topology},
+    statistics::{BoardKey, GameRecord, Statistics},
+};
+
+fn key() -> BoardKey {
+    BoardKey {
+        game_size: (9, 9),
+        number_of_mines: 10,
+        generation: Generation::Classic,
+        topology: Topology::Square,
+    }
+}
+
+fn game(won: bool, seconds: u64, date: u64) -> GameRecord {
+    GameRecord {
+        won,
+        metrics: Metrics {
+            time: Duration::from_secs(seconds),
+            three_bv: 20,
+            solved_three_bv: 20,
+            clicks: Clicks::default(),
+        },
+        date,
+    }
+}
+
+#[test]
+fn counts_games_and_streaks() {
+    let mut statistics = Statistics::default();
+    for (date, won) in [true, true, false, true].iter().enumerate() {
+        statistics.record(&key(), &game(*won, 60, date as u64));
+    }
+
+    let board = statistics.board(&key()).unwrap();
+    assert_eq!(board.played, 4);
+    assert_eq!(board.won, 3);
+    assert_eq!(board.current_streak, 1);
+    assert_eq!(board.best_streak, 2);
+    assert_eq!(board.win_rate(), 75.0);
+    assert_eq!(board.key.name(), "Beginner");
+}
+
+#[test]
+fn keeps_the_fastest_times_in_order() {
+    let mut statistics = Statistics::default();
+    for (date, seconds) in [50, 30, 70, 10, 40, 60, 20].iter().enumerate() {
+        statistics.record(&key(), &game(true, *seconds, date as u64));
+    }
+
+    let times: Vec<u64> = statistics
+        .board(&key())
+        .unwrap()
+        .best_times
+        .iter()
+        .map(|score| score.time.as_secs())
+        .collect();
+    assert_eq!(times, vec![10, 20, 30, 40, 50]);
+    assert!(!statistics.record(&key(), &game(true, 90, 100)));
+    assert!(statistics.record(&key(), &game(true, 15, 101)));
+}
+
+#[test]
+fn lost_games_are_not_best_times() {
+    let mut statistics = Statistics::default();
+    assert!(!statistics.record(&key(), &game(false, 5, 0)));
+    assert!(statistics.board(&key()).unwrap().best_times.is_empty());
+}
+
+#[test]
+fn names_the_best_time_of_the_game() {
+    let mut statistics = Statistics::default();
+    statistics.record(&key(), &game(true, 30, 1));
+    statistics.record(&key(), &game(true, 20, 2));
+    statistics.set_name(&key(), 1, "Ada");
+
+    let board = statistics.board(&key()).unwrap();
+    assert_eq!(board.best_times[1].name, "Ada");
+    assert_eq!(board.best_times[0].name, "");
+    assert_eq!(statistics.last_name, "Ada");
+
+    // The last name is used for the next best time
+    statistics.record(&key(), &game(true, 10, 3));
+    assert_eq!(statistics.board(&key()).unwrap().best_times[0].name, "Ada");
+}
+
+#[test]
+fn boards_are_kept_apart() {
+    let mut statistics = Statistics::default();
+    let no_guess = BoardKey {
+        generation: Generation::NoGuess,
+        ..key()
+    };
+    statistics.record(&key(), &game(true, 30, 1));
+    statistics.record(&no_guess, &game(false, 30, 2));
+
+    assert_eq!(statistics.boards.len(), 2);
+    assert_eq!(statistics.board(&no_guess).unwrap().won, 0);
+}
+
+#[test]
+fn other_shapes_are_not_difficulties() {
+    assert_eq!(key().name(), "Beginner");
+
+    let hex = BoardKey {
+        topology: Topology::Hex,
+        ..key()
+    };
+    assert_eq!(hex.name(), "Custom");
+    assert_eq!(hex.description(), "9x9, 10 mines, Classic, Hex");
+}
@@ -0,0 +1,70 @@
+use ggsweep::board::{Board, IndexType, SquareState, Status};
+
+/// Count the clicks it takes to win by only revealing squares that are still closed
+fn clicks_to_win(board: &mut Board) -> usize {
+    let safe: Vec<IndexType> = (0..board.len()).filter(|i| !board.is_mine(*i)).collect();
+    let mut clicks = 1;
+    for index in safe {
+        if let SquareState::Closed(_) = board.square(index) {
+            board.reveal(index);
+            clicks += 1;
+        }
+    }
+    clicks
+}
+
+#[test]
+fn three_bv_is_zero_before_generation() {
+    let board = Board::new((9, 9), 10, 1);
+    assert_eq!(board.three_bv(), 0);
+    assert_eq!(board.solved_three_bv(), 0);
+}
+
+#[test]
+fn board_without_mines_is_one_click() {
+    let mut board = Board::new((5, 5), 0, 1);
+    board.reveal(0);
+    assert_eq!(board.three_bv(), 1);
+}
+
+#[test]
+fn three_bv_counts_openings_and_isolated_numbers() {
+    // Two openings at the ends with the 2 between the mines on its own
+    let mut board = Board::new((7, 1), 2, 0).set_mines(vec![2, 4]);
+    assert_eq!(board.three_bv(), 3);
+
+    board.reveal(0);
+    assert_eq!(board.solved_three_bv(), 1);
+    board.reveal(3);
+    assert_eq!(board.solved_three_bv(), 2);
+
+    // The top two rows are one opening, the 2s between the mines at the bottom are on their own
+    let board = Board::new((5, 3), 3, 0).set_mines(vec![10, 12, 14]);
+    assert_eq!(board.three_bv(), 3);
+}
+
+#[test]
+fn three_bv_is_at_most_the_clicks_needed() {
+    for seed in 0..20 {
+        let mut board = Board::new((9, 9), 10, seed);
+        board.reveal(40);
+        let three_bv = board.three_bv();
+        assert!(three_bv >= 1);
+
+        // Reveals in index order never need fewer clicks than the 3BV
+        let clicks = clicks_to_win(&mut board);
+        assert_eq!(board.status(), Status::Won);
+        assert!(three_bv <= clicks, "seed {}", seed);
+    }
+}
+
+#[test]
+fn solved_three_bv_grows_to_the_three_bv() {
+    let mut board = Board::new((9, 9), 10, 4);
+    board.reveal(40);
+    let three_bv = board.three_bv();
+    assert!(board.solved_three_bv() <= three_bv);
+
+    clicks_to_win(&mut board);
+    assert_eq!(board.solved_three_bv(), three_bv);
+}
@@ -0,0 +1,137 @@
+use std::collections::HashSet;
+
+use ggsweep::board::{bot, Board, Generation, IndexType, Status, Topology};
+
+const TOPOLOGIES: [Topology; 3] = [Topology::Square, Topology::Hex, Topology::Triangle];
+
+fn neighbors(board: &Board, index: IndexType) -> HashSet<IndexType> {
+    board
+        .get_neighbors(index)
+        .iter()
+        .flatten()
+        .copied()
+        .collect()
+}
+
+#[test]
+fn neighbors_go_both_ways() {
+    for &topology in &TOPOLOGIES {
+        let board = Board::new((7, 6), 5, 0).set_topology(topology);
+
+        for index in 0..board.len() {
+            for neighbor in neighbors(&board, index) {
+                assert_ne!(neighbor, index, "{:?}", topology);
+                assert!(
+                    neighbors(&board, neighbor).contains(&index),
+                    "{:?}: {} is next to {} but not the other way",
+                    topology,
+                    neighbor,
+                    index
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn squares_away_from_the_edges_have_every_neighbor() {
+    for &topology in &TOPOLOGIES {
+        let board = Board::new((9, 9), 10, 0).set_topology(topology);
+
+        for y in 2..7 {
+            for x in 2..7 {
+                let index = board.point_to_index(cgmath::vec2(x, y));
+                assert_eq!(
+                    neighbors(&board, index).len(),
+                    topology.max_neighbors(),
+                    "{:?} at {}, {}",
+                    topology,
+                    x,
+                    y
+                );
+            }
+        }
+    }
+}
+
+#[test]
+fn odd_hex_rows_are_pushed_right() {
+    let board = Board::new((5, 5), 5, 0).set_topology(Topology::Hex);
+    let at = |x, y| board.point_to_index(cgmath::vec2(x, y));
+
+    let even: HashSet<_> = [at(1, 1), at(2, 1), at(1, 2), at(3, 2), at(1, 3), at(2, 3)]
+        .iter()
+        .copied()
+        .collect();
+    assert_eq!(neighbors(&board, at(2, 2)), even);
+
+    let odd: HashSet<_> = [at(2, 0), at(3, 0), at(1, 1), at(3, 1), at(2, 2), at(3, 2)]
+        .iter()
+        .copied()
+        .collect();
+    assert_eq!(neighbors(&board, at(2, 1)), odd);
+}
+
+/// The corners of a triangle on a grid where x counts half triangles and y counts rows
+fn triangle_corners(x: i32, y: i32) -> [(i32, i32); 3] {
+    if Topology::points_up(cgmath::vec2(x, y)) {
+        [(x + 1, y), (x, y + 1), (x + 2, y + 1)]
+    } else {
+        [(x, y), (x + 2, y), (x + 1, y + 1)]
+    }
+}
+
+#[test]
+fn triangles_are_neighbors_when_they_share_a_corner() {
+    let board = Board::new((8, 6), 5, 0).set_topology(Topology::Triangle);
+
+    for index in 0..board.len() {
+        let point = board.index_to_point(index);
+        let corners = triangle_corners(point.x, point.y);
+
+        let touching: HashSet<IndexType> = (0..board.len())
+            .filter(|other| *other != index)
+            .filter(|other| {
+                let other = board.index_to_point(*other);
+                triangle_corners(other.x, other.y)
+                    .iter()
+                    .any(|corner| corners.contains(corner))
+            })
+            .collect();
+
+        assert_eq!(neighbors(&board, index), touching, "triangle {}", index);
+    }
+}
+
+#[test]
+fn the_safe_zone_covers_every_neighbor() {
+    for &topology in &TOPOLOGIES {
+        for seed in 0..10 {
+            let mut board = Board::new((10, 10), 40, seed)
+                .set_topology(topology)
+                .set_generation(Generation::SafeZone);
+            let first = board.point_to_index(cgmath::vec2(5, 5));
+            board.reveal(first);
+
+            assert!(!board.is_mine(first), "{:?}", topology);
+            for neighbor in neighbors(&board, first) {
+                assert!(!board.is_mine(neighbor), "{:?} seed {}", topology, seed);
+            }
+        }
+    }
+}
+
+#[test]
+fn no_guess_boards_of_every_shape_are_solved() {
+    for &topology in &TOPOLOGIES {
+        for seed in 0..5 {
+            let board = Board::new((9, 9), 10, seed)
+                .set_topology(topology)
+                .set_generation(Generation::NoGuess);
+            let game = bot::play(board);
+
+            assert_eq!(game.status, Status::Won, "{:?} seed {}", topology, seed);
+            assert_eq!(game.guesses, 0, "{:?} seed {}", topology, seed);
+        }
+    }
+}
@@ -0,0 +1,109 @@
+use ggsweep::board::{Board, IndexType, Mark, SquareState, Status, WinCondition};
+
+/// A 5x5 board with 3 mines where the first square in the corner has been revealed
+fn started_board(win_condition: WinCondition) -> Board {
+    let mut board = Board::new((5, 5), 3, 7).set_win_condition(win_condition);
+    board.reveal(0);
+    board
+}
+
+fn safe_squares(board: &Board) -> Vec<IndexType> {
+    (0..board.len()).filter(|i| !board.is_mine(*i)).collect()
+}
+
+fn reveal_all_safe(board: &mut Board) {
+    for index in safe_squares(board) {
+        board.reveal(index);
+    }
+}
+
+fn flag_all_mines(board: &mut Board) {
+    let mut mines: Vec<IndexType> = board.mines().iter().copied().collect();
+    mines.sort_unstable();
+    for index in mines {
+        if board.square(index) == &SquareState::Closed(Mark::None) {
+            board.toggle_flag(index);
+        }
+    }
+}
+
+#[test]
+fn reveal_all_safe_wins_by_revealing() {
+    let mut board = started_board(WinCondition::RevealAllSafe);
+    reveal_all_safe(&mut board);
+    assert_eq!(board.status(), Status::Won);
+}
+
+#[test]
+fn reveal_all_safe_does_not_win_by_flagging() {
+    let mut board = started_board(WinCondition::RevealAllSafe);
+    flag_all_mines(&mut board);
+    assert_eq!(board.status(), Status::InGame);
+}
+
+#[test]
+fn flag_all_mines_wins_by_flagging() {
+    let mut board = started_board(WinCondition::FlagAllMines);
+    flag_all_mines(&mut board);
+    assert_eq!(board.status(), Status::Won);
+}
+
+#[test]
+fn flag_all_mines_does_not_win_by_revealing() {
+    let mut board = started_board(WinCondition::FlagAllMines);
+    reveal_all_safe(&mut board);
+    assert_eq!(board.status(), Status::InGame);
+
+    flag_all_mines(&mut board);
+    assert_eq!(board.status(), Status::Won);
+}
+
+#[test]
+fn flag_all_mines_does_not_win_with_extra_flags() {
+    // The first square is safe and the last one is a mine
+    let mut board = Board::new((3, 1), 1, 0)
+        .set_win_condition(WinCondition::FlagAllMines)
+        .set_mines(vec![2]);
+    board.toggle_flag(0);
+    board.toggle_flag(2);
+    assert_eq!(board.status(), Status::InGame);
+
+    board.toggle_flag(0);
+    assert_eq!(board.status(), Status::Won);
+}
+
+#[test]
+fn flags_before_the_first_reveal_do_not_win() {
+    let mut board = Board::new((5, 5), 3, 7).set_win_condition(WinCondition::FlagAllMines);
+    board.toggle_flag(4);
+    board.toggle_flag(4);
+    assert_eq!(board.status(), Status::InGame);
+}
+
+#[test]
+fn both_needs_revealing_and_flagging() {
+    let mut board = started_board(WinCondition::Both);
+    reveal_all_safe(&mut board);
+    assert_eq!(board.status(), Status::InGame);
+
+    flag_all_mines(&mut board);
+    assert_eq!(board.status(), Status::Won);
+}
+
+#[test]
+fn both_is_not_won_by_flagging_alone() {
+    let mut board = started_board(WinCondition::Both);
+    flag_all_mines(&mut board);
+    assert_eq!(board.status(), Status::InGame);
+}
+
+#[test]
+fn revealing_a_mine_loses_and_remembers_it() {
+    let mut board = started_board(WinCondition::RevealAllSafe);
+    assert_eq!(board.detonated(), None);
+
+    let mine = *board.mines().iter().min().unwrap();
+    board.reveal(mine);
+    assert_eq!(board.status(), Status::Lost);
+    assert_eq!(board.detonated(), Some(mine));
+}